@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use rdp::{decimate_by_tolerance, decimate_by_tolerance_in_place, Curve};
+
+const POINTS: usize = 100_000;
+const RUNS: u32 = 10;
+
+// the `random_tolerance` test data
+const TIMESTAMPS: [u16; 77] = [
+    0, 8, 16, 24, 33, 41, 48, 57, 64, 72, 80, 89, 97, 105, 112, 120, 128, 137, 145, 153, 161, 168,
+    176, 184, 193, 201, 208, 217, 224, 232, 240, 250, 256, 264, 272, 280, 288, 297, 305, 312, 320,
+    328, 336, 344, 353, 360, 368, 376, 384, 392, 401, 409, 416, 424, 433, 440, 448, 457, 465, 472,
+    480, 488, 496, 505, 513, 521, 528, 536, 544, 552, 560, 569, 576, 584, 592, 600, 648,
+];
+const X: [u16; 77] = [
+    77, 86, 100, 115, 143, 173, 209, 255, 304, 358, 412, 461, 499, 527, 552, 569, 584, 599, 609,
+    621, 636, 648, 660, 674, 684, 699, 714, 728, 741, 756, 768, 781, 791, 797, 806, 814, 821, 826,
+    832, 837, 844, 849, 856, 860, 864, 869, 876, 880, 886, 891, 895, 896, 899, 901, 903, 904, 905,
+    907, 908, 908, 909, 912, 912, 914, 914, 915, 916, 916, 916, 917, 917, 917, 919, 919, 919, 919,
+    919,
+];
+const Y: [u16; 77] = [
+    54, 62, 74, 88, 111, 134, 162, 192, 229, 264, 304, 336, 364, 386, 404, 420, 432, 444, 455, 467,
+    484, 499, 513, 530, 543, 560, 577, 592, 608, 623, 634, 645, 654, 661, 669, 674, 680, 688, 693,
+    698, 703, 708, 715, 718, 724, 728, 733, 738, 741, 745, 748, 750, 752, 756, 757, 760, 762, 765,
+    767, 768, 772, 776, 780, 784, 786, 788, 791, 792, 795, 796, 798, 800, 801, 802, 803, 804, 805,
+];
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    // tile the data up to `POINTS` points, shifting every copy so it starts one sampling interval
+    // after, and where, the previous copy ended
+    let tile = TIMESTAMPS.len();
+    let duration = (TIMESTAMPS[tile - 1] + 8) as f64;
+    let (dx, dy) = ((X[tile - 1] - X[0]) as f64, (Y[tile - 1] - Y[0]) as f64);
+    let copy = |i: usize| (i / tile) as f64;
+    let timestamps: Vec<f64> = (0..POINTS)
+        .map(|i| TIMESTAMPS[i % tile] as f64 + copy(i) * duration)
+        .collect();
+    let x: Vec<f64> = (0..POINTS)
+        .map(|i| X[i % tile] as f64 + copy(i) * dx)
+        .collect();
+    let y: Vec<f64> = (0..POINTS)
+        .map(|i| Y[i % tile] as f64 + copy(i) * dy)
+        .collect();
+
+    let curve = Curve::from_vectors(timestamps.clone(), x.clone(), y.clone()).unwrap();
+
+    let allocating = time(|| {
+        decimate_by_tolerance(&timestamps, &x, &y, 5.0).unwrap();
+    });
+    let in_place = time(|| {
+        let mut curve = curve.clone();
+        decimate_by_tolerance_in_place(&mut curve, 5.0).unwrap();
+    });
+
+    println!("{} points", POINTS);
+    println!("allocating: {:?}", allocating);
+    println!(
+        "in place:   {:?} (including a clone of the input)",
+        in_place
+    );
+}
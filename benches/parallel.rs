@@ -0,0 +1,34 @@
+use std::time::{Duration, Instant};
+
+use rdp::{decimate_by_tolerance, decimate_by_tolerance_parallel};
+
+const POINTS: usize = 200_000;
+const RUNS: u32 = 10;
+
+fn time(f: impl Fn()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let timestamps: Vec<f64> = (0..POINTS).map(|i| i as f64 / 100.0).collect();
+    let x: Vec<f64> = timestamps.iter().map(|&t| t.cos() * t).collect();
+    let y: Vec<f64> = timestamps
+        .iter()
+        .map(|&t| t.sin() * t + (t * 37.0).sin())
+        .collect();
+
+    let sequential = time(|| {
+        decimate_by_tolerance(&timestamps, &x, &y, 0.1).unwrap();
+    });
+    let parallel = time(|| {
+        decimate_by_tolerance_parallel(&timestamps, &x, &y, 0.1).unwrap();
+    });
+
+    println!("{} points", POINTS);
+    println!("sequential: {:?}", sequential);
+    println!("parallel:   {:?}", parallel);
+}
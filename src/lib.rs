@@ -1,16 +1,34 @@
-use js_sys::{Float64Array, JsString};
+use js_sys::{
+    Array, Float64Array, Function, JsString, Number, Object, Reflect, Uint32Array, Uint8Array,
+    Uint8ClampedArray,
+};
 use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
 
 const MAX_BINARY_SEARCH_ITERATIONS: usize = 500;
+const STALL_ITERATIONS: usize = 8;
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_POINTS: usize = 4096;
+const BINARY_MAGIC: &[u8; 4] = b"RDPC";
+const WKB_LINE_STRING: u32 = 2;
+const WKB_LINE_STRING_Z: u32 = 1002;
+/// spread of the local deviations, relative to the mean segment length, above which
+/// `decimate_adaptive` treats a curve as noisy
+const ADAPTIVE_NOISE_THRESHOLD: f64 = 0.25;
+/// mean turning angle in radians above which `decimate_adaptive` treats a curve as winding
+const ADAPTIVE_TURNING_THRESHOLD: f64 = 0.5;
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
     fn log(s: &str);
+
+    #[wasm_bindgen(js_namespace = console, catch)]
+    fn table(data: &JsValue) -> Result<(), JsValue>;
 }
 
 #[wasm_bindgen]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Curve {
     timestamps: Vec<f64>,
     x: Vec<f64>,
@@ -23,6 +41,29 @@ impl Curve {
         log(format!("{:#?}", self).as_str());
     }
 
+    /// logs the points with `console.table` as objects with `t`, `x` and `y` properties, or as a
+    /// text table with `console.log` where `console.table` isn't available
+    pub fn debug_print_table(&self) {
+        let rows: Array = (0..self.len())
+            .map(|i| {
+                let row = Object::new();
+                for (key, value) in [
+                    ("t", self.timestamps[i]),
+                    ("x", self.x[i]),
+                    ("y", self.y[i]),
+                ] {
+                    Reflect::set(&row, &key.into(), &value.into())
+                        .expect("Setting a property on a plain object should not fail.");
+                }
+                row
+            })
+            .collect();
+
+        if table(&rows).is_err() {
+            log(&self.to_table_string());
+        }
+    }
+
     pub fn get_timestamps(&self) -> Float64Array {
         Float64Array::from(&self.timestamps[..])
     }
@@ -35,313 +76,8019 @@ impl Curve {
         Float64Array::from(&self.y[..])
     }
 
+    /// non-finite values are written as empty fields
     pub fn get_csv(&self) -> JsString {
-        let mut output = String::from("timestamps, x, y\n");
+        JsString::from(self.to_delimited_string(", ", ""))
+    }
 
-        for i in 0..self.x.len() {
-            output.push_str(&format!(
-                "{}, {}, {}\n",
-                self.timestamps[i], self.x[i], self.y[i]
-            ))
-        }
+    /// like `get_csv` but with a custom delimiter and text for non-finite values
+    pub fn get_delimited(&self, delimiter: &str, non_finite: &str) -> JsString {
+        JsString::from(self.to_delimited_string(delimiter, non_finite))
+    }
 
-        JsString::from(output)
+    /// reverses the order of the points in place
+    pub fn reverse(&mut self) {
+        self.timestamps.reverse();
+        self.x.reverse();
+        self.y.reverse();
     }
-}
 
-#[wasm_bindgen]
-pub fn decimate_by_tolerance(
-    timestamps: &[f64],
-    x: &[f64],
-    y: &[f64],
-    tolerance: f64,
-) -> Result<Curve, String> {
-    if (timestamps.len() != x.len()) || (timestamps.len() != y.len()) {
-        return Err(
-            "The number of values for timestamps, x-coordinates and y-coordinates don't match."
-                .to_string(),
-        );
+    /// returns a copy of the curve with the points in reverse order
+    pub fn reversed(&self) -> Curve {
+        let mut curve = self.clone();
+        curve.reverse();
+        curve
     }
 
-    let mut decimated_curve = Curve {
-        timestamps: vec![timestamps[0], timestamps[timestamps.len() - 1]],
-        x: vec![x[0], x[x.len() - 1]],
-        y: vec![y[0], y[y.len() - 1]],
-    };
-    let mut stack: Vec<(usize, usize)> = vec![(0, timestamps.len() - 1)];
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
 
-    while let Some((start, end)) = stack.pop() {
-        let mut dmax = perpendicular_distance(
-            x[start + 1],
-            y[start + 1],
-            x[start],
-            y[start],
-            x[end],
-            y[end],
-        );
-        let mut dmax_is_at = start + 1;
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
 
-        for i in (start + 2)..(end) {
-            let d = perpendicular_distance(x[i], y[i], x[start], y[start], x[end], y[end]);
+    /// returns `[timestamp, x, y]` for the point at `index`, or an empty array if it is out of bounds
+    #[wasm_bindgen(js_name = get_point)]
+    pub fn get_point_js(&self, index: usize) -> Float64Array {
+        point_to_array(self.get_point(index))
+    }
 
-            if d > dmax {
-                dmax = d;
-                dmax_is_at = i;
-            }
-        }
+    #[wasm_bindgen(js_name = get_first)]
+    pub fn get_first_js(&self) -> Float64Array {
+        point_to_array(self.get_first())
+    }
 
-        if dmax > tolerance {
-            // find the end value's index in the final curve and insert this element just before it
-            let index = decimated_curve
-                .timestamps
-                .iter()
-                .position(|&i| i == timestamps[end])
-                .expect("The 'end' value should already exist in the final curve.");
-            decimated_curve
-                .timestamps
-                .insert(index, timestamps[dmax_is_at]);
-            decimated_curve.x.insert(index, x[dmax_is_at]);
-            decimated_curve.y.insert(index, y[dmax_is_at]);
+    #[wasm_bindgen(js_name = get_last)]
+    pub fn get_last_js(&self) -> Float64Array {
+        point_to_array(self.get_last())
+    }
 
-            stack.push((start, dmax_is_at));
-            stack.push((dmax_is_at, end));
+    /// rounds every coordinate to the nearest multiple of the given precision
+    pub fn quantize(&self, x_precision: f64, y_precision: f64) -> Result<Curve, String> {
+        if x_precision == 0.0 || y_precision == 0.0 {
+            return Err("The precision for quantization must be non-zero.".to_string());
         }
+
+        Ok(Curve {
+            timestamps: self.timestamps.clone(),
+            x: self
+                .x
+                .iter()
+                .map(|&x| (x / x_precision).round() * x_precision)
+                .collect(),
+            y: self
+                .y
+                .iter()
+                .map(|&y| (y / y_precision).round() * y_precision)
+                .collect(),
+        })
     }
 
-    Ok(decimated_curve)
-}
+    /// the largest distance from a point of this curve to the closest point of `other`
+    pub fn directed_hausdorff_distance(&self, other: &Curve) -> f64 {
+        let mut max_distance: f64 = 0.0;
 
-#[wasm_bindgen]
-pub fn decimate_to_count(
-    timestamps: &[f64],
-    x: &[f64],
-    y: &[f64],
-    count: usize,
-) -> Result<Curve, String> {
-    if timestamps.len() < count {
-        return Err("The curve does not have enough points.".to_string());
+        for i in 0..self.len() {
+            let mut min_distance = f64::INFINITY;
+
+            for j in 0..other.len() {
+                min_distance =
+                    min_distance.min(distance(self.x[i], self.y[i], other.x[j], other.y[j]));
+            }
+
+            max_distance = max_distance.max(min_distance);
+        }
+
+        max_distance
     }
 
-    if (timestamps.len() != x.len()) || (timestamps.len() != y.len()) {
-        return Err(
-            "The number of values for timestamps, x-coordinates and y-coordinates don't match."
-                .to_string(),
-        );
+    /// symmetric hausdorff distance between the points of two curves
+    pub fn hausdorff_distance(&self, other: &Curve) -> f64 {
+        self.directed_hausdorff_distance(other)
+            .max(other.directed_hausdorff_distance(self))
     }
 
-    let mut max_distance = distance(x[0], y[0], x[1], y[1]);
+    /// computes the statistics of all channels in a single pass, the means and variances are NaN for an empty curve
+    pub fn statistics(&self) -> CurveStatistics {
+        let mut stats = CurveStatistics {
+            x_mean: 0.0,
+            x_variance: 0.0,
+            x_min: f64::INFINITY,
+            x_max: f64::NEG_INFINITY,
+            y_mean: 0.0,
+            y_variance: 0.0,
+            y_min: f64::INFINITY,
+            y_max: f64::NEG_INFINITY,
+            t_min: f64::INFINITY,
+            t_max: f64::NEG_INFINITY,
+            point_count: self.len(),
+        };
 
-    for i in 1..(x.len() - 1) {
-        if distance(x[i], y[i], x[i + 1], y[i + 1]) > max_distance {
-            max_distance = distance(x[i], y[i], x[i + 1], y[i + 1]);
-        }
-    }
+        // Welford's online algorithm, the variances hold the sum of squared differences until the end
+        for i in 0..self.len() {
+            let n = (i + 1) as f64;
 
-    let mut lower_limit = 0.0;
-    let mut upper_limit = max_distance;
-    let mut middle: f64;
-    let mut curve = Curve {
-        timestamps: Vec::new(),
-        x: Vec::new(),
-        y: Vec::new(),
-    };
+            let dx = self.x[i] - stats.x_mean;
+            stats.x_mean += dx / n;
+            stats.x_variance += dx * (self.x[i] - stats.x_mean);
 
-    // The loop may hit the limit if two values are somehow removed at the same(or almost the same) tolerance value.
-    for _ in 0..MAX_BINARY_SEARCH_ITERATIONS {
-        middle = (upper_limit + lower_limit) / 2.0;
-        curve = decimate_by_tolerance(timestamps, x, y, middle)?;
+            let dy = self.y[i] - stats.y_mean;
+            stats.y_mean += dy / n;
+            stats.y_variance += dy * (self.y[i] - stats.y_mean);
+
+            stats.x_min = stats.x_min.min(self.x[i]);
+            stats.x_max = stats.x_max.max(self.x[i]);
+            stats.y_min = stats.y_min.min(self.y[i]);
+            stats.y_max = stats.y_max.max(self.y[i]);
+            stats.t_min = stats.t_min.min(self.timestamps[i]);
+            stats.t_max = stats.t_max.max(self.timestamps[i]);
+        }
 
-        match curve.timestamps.len().cmp(&count) {
-            std::cmp::Ordering::Equal => return Ok(curve),
-            std::cmp::Ordering::Greater => lower_limit = middle,
-            std::cmp::Ordering::Less => upper_limit = middle,
+        let n = self.len() as f64;
+        if self.is_empty() {
+            stats.x_mean = f64::NAN;
+            stats.y_mean = f64::NAN;
         }
+        stats.x_variance /= n;
+        stats.y_variance /= n;
+
+        stats
     }
 
-    Err(format!(
-        "Binary Search limit reached. Count: {} Middle: {}",
-        curve.timestamps.len(),
-        (upper_limit + lower_limit) / 2.0
-    ))
-}
+    pub fn from_js_arrays(
+        timestamps: Float64Array,
+        x: Float64Array,
+        y: Float64Array,
+    ) -> Result<Curve, JsValue> {
+        Curve::from_vectors(timestamps.to_vec(), x.to_vec(), y.to_vec()).map_err(JsValue::from)
+    }
 
-/// perpendicular distance between a point and a line defined by two points
-/// https://math.stackexchange.com/a/2757330
-fn perpendicular_distance(x: f64, y: f64, x1: f64, y1: f64, xn: f64, yn: f64) -> f64 {
-    let numerator = ((xn - x1) * (y - y1) - (yn - y1) * (x - x1)).abs();
-    let denominator = ((xn - x1).powi(2) + (yn - y1).powi(2)).sqrt();
+    /// scales the coordinates into `[0, 1]` using the bounding box, a channel without any spread is mapped to 0.5
+    pub fn normalize(&self) -> Curve {
+        let (x_min, x_max, y_min, y_max) = self.bounding_box();
 
-    numerator / denominator
-}
+        Curve {
+            timestamps: self.timestamps.clone(),
+            x: self
+                .x
+                .iter()
+                .map(|&x| normalize_value(x, x_min, x_max))
+                .collect(),
+            y: self
+                .y
+                .iter()
+                .map(|&y| normalize_value(y, y_min, y_max))
+                .collect(),
+        }
+    }
 
-fn distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
-    ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
-}
+    /// maps coordinates in `[0, 1]` back into the given bounding box
+    pub fn denormalize(&self, x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Curve {
+        self.scale(x_max - x_min, y_max - y_min)
+            .translate(x_min, y_min)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// returns `[x, y]` of the centroid
+    #[wasm_bindgen(js_name = centroid)]
+    pub fn centroid_js(&self) -> Float64Array {
+        let (x, y) = self.centroid();
+        Float64Array::from(&[x, y][..])
+    }
 
-    #[test]
-    fn test_perpendicular_distance_on_the_line() {
-        let x = 2.0;
-        let y = 2.0;
-        let x1 = 0.0;
-        let y1 = 0.0;
-        let xn = 4.0;
-        let yn = 4.0;
+    #[wasm_bindgen(js_name = compute_derivatives)]
+    pub fn compute_derivatives_js(&self) -> CurveDerivatives {
+        let (dx_dt, dy_dt) = self.compute_derivatives();
+        CurveDerivatives { dx_dt, dy_dt }
+    }
 
-        let result = perpendicular_distance(x, y, x1, y1, xn, yn);
-        assert!(
-            (result - 0.0).abs() < 1e-10,
-            "Expected distance to be 0, got {}",
-            result
-        );
+    pub fn get_binary(&self) -> Uint8Array {
+        Uint8Array::from(&self.to_binary()[..])
     }
 
-    #[test]
-    fn test_perpendicular_distance_off_the_line() {
-        let x = 5.0;
-        let y = 11.0;
-        let x1 = 0.0;
-        let y1 = 0.0;
-        let xn = 20.0;
-        let yn = 20.0;
+    pub fn from_uint8_array(data: Uint8Array) -> Result<Curve, JsValue> {
+        Curve::from_binary(&data.to_vec()).map_err(JsValue::from)
+    }
 
-        let result = perpendicular_distance(x, y, x1, y1, xn, yn);
-        assert!(
-            (result - (3.0 * (2.0_f64).sqrt())).abs() < 1e-10,
-            "Expected distance to be approximately {}, got {}",
-            3.0 / (2.0_f64).sqrt(),
-            result
-        );
+    /// the binary serialization as standard base64, for URLs and JSON string fields
+    pub fn encode_to_base64(&self) -> JsString {
+        JsString::from(base64_encode(&self.to_binary()))
     }
 
-    fn assert_curve(actual: &Curve, expected: &Curve) {
-        assert_eq!(actual.timestamps, expected.timestamps);
-        assert_eq!(actual.x, expected.x);
-        assert_eq!(actual.y, expected.y);
+    pub fn decode_from_base64(s: &str) -> Result<Curve, JsValue> {
+        Curve::from_base64(s).map_err(JsValue::from)
     }
 
-    #[test]
-    fn all_points_pruned() {
-        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
-        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
-        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
-        let tolerance = 100.0;
+    /// decimates the curve in windows of `window_size` points that share their boundary points,
+    /// which is faster than decimating the whole curve at once but may retain a few more points
+    pub fn window_decimate(&self, window_size: usize, tolerance: f64) -> Result<Curve, String> {
+        if window_size < 2 {
+            return Err("The window size must be at least two.".to_string());
+        }
 
-        let result = decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap();
+        if self.len() < 2 {
+            return Err("Curve must contain at least two points.".to_string());
+        }
 
-        let expected_curve = Curve {
-            timestamps: vec![0.0, 4.0], // Only the start and end points are retained
-            x: vec![0.0, 4.0],
-            y: vec![0.0, 2.0],
+        let mut curve = Curve {
+            timestamps: vec![self.timestamps[0]],
+            x: vec![self.x[0]],
+            y: vec![self.y[0]],
         };
+        let mut start = 0;
 
-        assert_curve(&result, &expected_curve);
+        while start < self.len() - 1 {
+            let end = (start + window_size).min(self.len());
+            let window = decimate_by_tolerance(
+                &self.timestamps[start..end],
+                &self.x[start..end],
+                &self.y[start..end],
+                tolerance,
+            )?;
+
+            // the first point of each window is the last point of the previous one
+            curve.timestamps.extend_from_slice(&window.timestamps[1..]);
+            curve.x.extend_from_slice(&window.x[1..]);
+            curve.y.extend_from_slice(&window.y[1..]);
+
+            start = end - 1;
+        }
+
+        Ok(curve)
     }
 
-    #[test]
-    fn no_points_pruned() {
-        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
-        let x = vec![0.0, 1.9, 4.0, 5.0, 4.0];
-        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
-        let tolerance = 0.0;
+    #[wasm_bindgen(js_name = peak_detection)]
+    pub fn peak_detection_js(&self, min_prominence: f64) -> Uint32Array {
+        indices_to_array(&self.peak_detection(min_prominence))
+    }
 
-        let result = decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap();
+    #[wasm_bindgen(js_name = threshold_crossings)]
+    pub fn threshold_crossings_js(&self, threshold: f64, channel: Channel) -> Uint32Array {
+        indices_to_array(&self.threshold_crossings(threshold, channel))
+    }
 
-        let expected_curve = Curve { timestamps, x, y };
+    #[wasm_bindgen(js_name = rising_crossings)]
+    pub fn rising_crossings_js(&self, threshold: f64, channel: Channel) -> Uint32Array {
+        indices_to_array(&self.rising_crossings(threshold, channel))
+    }
 
-        assert_curve(&result, &expected_curve);
+    #[wasm_bindgen(js_name = falling_crossings)]
+    pub fn falling_crossings_js(&self, threshold: f64, channel: Channel) -> Uint32Array {
+        indices_to_array(&self.falling_crossings(threshold, channel))
     }
 
-    #[test]
-    fn specific_count() {
-        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 3.5, 4.0];
-        let x = vec![0.0, 2.0, 67.2, 5.1, 6.0, 6.4];
-        let y = vec![0.0, 0.7, 1.0, 1.4, 2.2, 3.0];
+    /// the largest distance from a point of the polyline to the closest segment of this curve,
+    /// which is the error of a decimated curve against its original
+    pub fn hausdorff_distance_to_polyline(&self, px: &[f64], py: &[f64]) -> Result<f64, String> {
+        if px.len() != py.len() {
+            return Err("The number of x-coordinates and y-coordinates don't match.".to_string());
+        }
 
-        let result = decimate_to_count(&timestamps, &x, &y, 3).unwrap();
+        if self.is_empty() {
+            return Err("Curve must contain at least one point.".to_string());
+        }
 
-        let expected_curve = Curve {
-            timestamps: vec![0.0, 2.0, 4.0],
-            x: vec![0.0, 67.2, 6.4],
-            y: vec![0.0, 1.0, 3.0],
-        };
+        let mut max_distance: f64 = 0.0;
 
-        assert_curve(&result, &expected_curve);
+        for (&x, &y) in px.iter().zip(py) {
+            max_distance = max_distance.max(self.distance_to_point(x, y));
+        }
+
+        Ok(max_distance)
     }
 
-    #[test]
-    fn limit_reached() {
-        let timestamps = vec![0.0, 1.0, 2.0, 3.0];
-        let x = vec![0.0, 0.0, 1.0, 4.0];
-        let y = vec![0.0, 1.0, 0.0, 4.0];
+    /// inserts a point before `index`, keeping the timestamps strictly increasing
+    pub fn insert(&mut self, index: usize, timestamp: f64, x: f64, y: f64) -> Result<(), String> {
+        if index > self.len() {
+            return Err(format!(
+                "Index {} is out of bounds for a curve with {} points.",
+                index,
+                self.len()
+            ));
+        }
 
-        let result = decimate_to_count(&timestamps, &x, &y, 3);
+        if (index > 0 && timestamp <= self.timestamps[index - 1])
+            || (index < self.len() && timestamp >= self.timestamps[index])
+        {
+            return Err(format!(
+                "Inserting timestamp {} at index {} would break the order of the timestamps.",
+                timestamp, index
+            ));
+        }
 
-        assert!(result.is_err());
-        assert_eq!(
-            result.unwrap_err(),
-            "Binary Search limit reached. Count: 2 Middle: 0.7071067811865475".to_string()
-        );
+        self.timestamps.insert(index, timestamp);
+        self.x.insert(index, x);
+        self.y.insert(index, y);
+
+        Ok(())
     }
 
-    #[test]
-    fn random_tolerance() {
-        let timestamps: Vec<f64> = vec![
-            0, 8, 16, 24, 33, 41, 48, 57, 64, 72, 80, 89, 97, 105, 112, 120, 128, 137, 145, 153,
-            161, 168, 176, 184, 193, 201, 208, 217, 224, 232, 240, 250, 256, 264, 272, 280, 288,
-            297, 305, 312, 320, 328, 336, 344, 353, 360, 368, 376, 384, 392, 401, 409, 416, 424,
-            433, 440, 448, 457, 465, 472, 480, 488, 496, 505, 513, 521, 528, 536, 544, 552, 560,
-            569, 576, 584, 592, 600, 648,
-        ]
-        .iter()
-        .map(|&x| x as f64)
-        .collect();
-        let x: Vec<f64> = vec![
-            77, 86, 100, 115, 143, 173, 209, 255, 304, 358, 412, 461, 499, 527, 552, 569, 584, 599,
-            609, 621, 636, 648, 660, 674, 684, 699, 714, 728, 741, 756, 768, 781, 791, 797, 806,
-            814, 821, 826, 832, 837, 844, 849, 856, 860, 864, 869, 876, 880, 886, 891, 895, 896,
-            899, 901, 903, 904, 905, 907, 908, 908, 909, 912, 912, 914, 914, 915, 916, 916, 916,
-            917, 917, 917, 919, 919, 919, 919, 919,
-        ]
-        .iter()
-        .map(|&x| x as f64)
-        .collect();
-        let y: Vec<f64> = vec![
-            54, 62, 74, 88, 111, 134, 162, 192, 229, 264, 304, 336, 364, 386, 404, 420, 432, 444,
-            455, 467, 484, 499, 513, 530, 543, 560, 577, 592, 608, 623, 634, 645, 654, 661, 669,
-            674, 680, 688, 693, 698, 703, 708, 715, 718, 724, 728, 733, 738, 741, 745, 748, 750,
-            752, 756, 757, 760, 762, 765, 767, 768, 772, 776, 780, 784, 786, 788, 791, 792, 795,
-            796, 798, 800, 801, 802, 803, 804, 805,
-        ]
-        .iter()
-        .map(|&x| x as f64)
-        .collect();
-        let count = 13;
+    pub fn to_json(&self) -> JsString {
+        JsString::from(self.to_json_string())
+    }
 
-        let result = decimate_to_count(&timestamps, &x, &y, count).unwrap();
+    pub fn from_json(json: &str) -> Result<Curve, JsValue> {
+        Curve::from_json_str(json).map_err(JsValue::from)
+    }
 
-        let expected_curve = Curve {
-            timestamps: vec![0, 33, 48, 89, 112, 137, 224, 288, 297, 353, 416, 488, 648]
+    /// discrete Fréchet distance, the shortest leash that lets two walkers traverse both curves in
+    /// order, infinite if either curve is empty
+    pub fn compute_frechet_distance(&self, other: &Curve) -> f64 {
+        if self.is_empty() || other.is_empty() {
+            return f64::INFINITY;
+        }
+
+        let m = other.len();
+        // coupling distances for the previous and the current point of self
+        let mut previous = vec![0.0; m];
+        let mut current = vec![0.0; m];
+
+        for i in 0..self.len() {
+            for j in 0..m {
+                let d = distance(self.x[i], self.y[i], other.x[j], other.y[j]);
+
+                current[j] = match (i, j) {
+                    (0, 0) => d,
+                    (0, _) => current[j - 1],
+                    (_, 0) => previous[0],
+                    _ => previous[j].min(previous[j - 1]).min(current[j - 1]),
+                };
+                current[j] = f64::max(current[j], d);
+            }
+
+            std::mem::swap(&mut previous, &mut current);
+        }
+
+        previous[m - 1]
+    }
+
+    /// removes the interior points whose distance to the chord between their neighbours is more
+    /// than `sigma` standard deviations above the mean of those distances
+    pub fn remove_outliers(&self, sigma: f64) -> Curve {
+        let deviations = self.local_deviations();
+        let n = deviations.len() as f64;
+        let mean = deviations.iter().sum::<f64>() / n;
+        let standard_deviation =
+            (deviations.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+        let mut curve = self.clone();
+        let mut removed = 0;
+
+        for (i, d) in deviations.iter().enumerate() {
+            if standard_deviation > 0.0 && (d - mean) / standard_deviation > sigma {
+                let index = i + 1 - removed;
+                curve.timestamps.remove(index);
+                curve.x.remove(index);
+                curve.y.remove(index);
+                removed += 1;
+            }
+        }
+
+        curve
+    }
+
+    /// swaps the x and y coordinates
+    pub fn flip_axes(&self) -> Curve {
+        Curve {
+            timestamps: self.timestamps.clone(),
+            x: self.y.clone(),
+            y: self.x.clone(),
+        }
+    }
+
+    pub fn translate(&self, dx: f64, dy: f64) -> Curve {
+        Curve {
+            timestamps: self.timestamps.clone(),
+            x: self.x.iter().map(|&x| x + dx).collect(),
+            y: self.y.iter().map(|&y| y + dy).collect(),
+        }
+    }
+
+    pub fn scale(&self, sx: f64, sy: f64) -> Curve {
+        Curve {
+            timestamps: self.timestamps.clone(),
+            x: self.x.iter().map(|&x| x * sx).collect(),
+            y: self.y.iter().map(|&y| y * sy).collect(),
+        }
+    }
+
+    pub fn contains_nonfinite(&self) -> bool {
+        self.timestamps
+            .iter()
+            .chain(&self.x)
+            .chain(&self.y)
+            .any(|value| !value.is_finite())
+    }
+
+    /// removes every point with a NaN or infinite timestamp or coordinate
+    pub fn sanitize(&self) -> Curve {
+        let indices: Vec<usize> = (0..self.len())
+            .filter(|&i| {
+                self.timestamps[i].is_finite() && self.x[i].is_finite() && self.y[i].is_finite()
+            })
+            .collect();
+
+        self.select(&indices)
+    }
+
+    /// `n` points evenly spaced along the path, including the first and last points
+    pub fn resample_by_arc_length(&self, n: usize) -> Result<Curve, String> {
+        if n < 2 {
+            return Err("The number of points must be at least two.".to_string());
+        }
+
+        if self.len() < 2 {
+            return Err("Curve must contain at least two points.".to_string());
+        }
+
+        let lengths = self.cumulative_lengths();
+        let total = lengths[lengths.len() - 1];
+
+        let mut curve = Curve {
+            timestamps: Vec::with_capacity(n),
+            x: Vec::with_capacity(n),
+            y: Vec::with_capacity(n),
+        };
+
+        for i in 0..n {
+            let (t, x, y) = if i == n - 1 {
+                (
+                    self.timestamps[self.len() - 1],
+                    self.x[self.len() - 1],
+                    self.y[self.len() - 1],
+                )
+            } else {
+                self.interpolate_at_length(&lengths, total * i as f64 / (n - 1) as f64)
+            };
+
+            curve.timestamps.push(t);
+            curve.x.push(x);
+            curve.y.push(y);
+        }
+
+        Ok(curve)
+    }
+
+    /// whether any two non-adjacent segments of the path intersect
+    pub fn has_self_intersections(&self) -> bool {
+        !self.self_intersections(true).is_empty()
+    }
+
+    /// every point where two non-adjacent segments of the path intersect, as `[x, y]` arrays
+    pub fn find_self_intersections(&self) -> Array {
+        self.self_intersections(false)
+            .into_iter()
+            .map(|(x, y)| Array::of2(&JsValue::from(x), &JsValue::from(y)))
+            .collect()
+    }
+
+    /// whether the first and last points are within `tolerance` of each other
+    pub fn is_closed(&self, tolerance: f64) -> bool {
+        match (self.get_first(), self.get_last()) {
+            (Some((_, x1, y1)), Some((_, x2, y2))) => distance(x1, y1, x2, y2) <= tolerance,
+            _ => false,
+        }
+    }
+
+    /// appends a copy of the first point unless the curve is already closed, timestamped one
+    /// average sampling interval after the last point
+    pub fn close(&mut self, tolerance: f64) {
+        if self.is_empty() || self.is_closed(tolerance) {
+            return;
+        }
+
+        let last = self.len() - 1;
+        let interval = (self.timestamps[last] - self.timestamps[0]) / last as f64;
+
+        self.timestamps.push(self.timestamps[last] + interval);
+        self.x.push(self.x[0]);
+        self.y.push(self.y[0]);
+    }
+
+    /// removes every interior point where the path turns by less than `min_angle_degrees`,
+    /// measured against its original neighbours
+    pub fn simplify_angles(&self, min_angle_degrees: f64) -> Curve {
+        if self.len() < 3 {
+            return self.clone();
+        }
+
+        let mut indices = vec![0];
+
+        for i in 1..self.len() - 1 {
+            if turn_angle(
+                (self.x[i - 1], self.y[i - 1]),
+                (self.x[i], self.y[i]),
+                (self.x[i + 1], self.y[i + 1]),
+            )
+            .to_degrees()
+                >= min_angle_degrees
+            {
+                indices.push(i);
+            }
+        }
+
+        indices.push(self.len() - 1);
+
+        self.select(&indices)
+    }
+
+    #[wasm_bindgen(js_name = sample)]
+    pub fn sample_js(&self, indices: Uint32Array) -> Result<Curve, String> {
+        let indices: Vec<usize> = indices.to_vec().into_iter().map(|i| i as usize).collect();
+        self.sample(&indices)
+    }
+
+    /// speed at every point, the magnitude of the `compute_derivatives` velocity
+    pub fn gradient_magnitude(&self) -> Vec<f64> {
+        let (dx_dt, dy_dt) = self.compute_derivatives();
+
+        dx_dt
+            .iter()
+            .zip(&dy_dt)
+            .map(|(dx, dy)| dx.hypot(*dy))
+            .collect()
+    }
+
+    /// time between the first and last points, `None` for curves with fewer than two points
+    pub fn time_span(&self) -> Option<f64> {
+        self.time_range()
+            .filter(|_| self.len() >= 2)
+            .map(|(start, end)| end - start)
+    }
+
+    #[wasm_bindgen(js_name = time_range)]
+    pub fn time_range_js(&self) -> Float64Array {
+        match self.time_range() {
+            Some((start, end)) => Float64Array::from(&[start, end][..]),
+            None => Float64Array::new_with_length(0),
+        }
+    }
+
+    /// removes every point that is less than `deadband` away from the previous retained point,
+    /// always keeping the first and last points
+    pub fn apply_deadband(&mut self, deadband: f64) {
+        if self.len() < 3 {
+            return;
+        }
+
+        let last = self.len() - 1;
+        let mut indices = vec![0];
+
+        for i in 1..last {
+            let previous = indices[indices.len() - 1];
+
+            if distance(self.x[previous], self.y[previous], self.x[i], self.y[i]) >= deadband {
+                indices.push(i);
+            }
+        }
+
+        indices.push(last);
+
+        *self = self.select(&indices);
+    }
+
+    /// clamps every x and y value into the given ranges
+    ///
+    /// This only clamps the values, it doesn't clip the path geometrically, so no points are
+    /// added where the path crosses the boundary.
+    pub fn clamp(&self, x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Result<Curve, String> {
+        if !(x_min <= x_max && y_min <= y_max) {
+            return Err("The minimum must not exceed the maximum.".to_string());
+        }
+
+        Ok(Curve {
+            timestamps: self.timestamps.clone(),
+            x: self.x.iter().map(|x| x.clamp(x_min, x_max)).collect(),
+            y: self.y.iter().map(|y| y.clamp(y_min, y_max)).collect(),
+        })
+    }
+
+    /// the vertices of the convex hull in counter-clockwise order, starting from the leftmost
+    /// point
+    pub fn compute_convex_hull(&self) -> Curve {
+        self.select(&self.convex_hull_indices())
+    }
+
+    /// the path in the Encoded Polyline Algorithm Format with `precision` decimal places, using
+    /// `y` as the latitude and `x` as the longitude
+    pub fn encode_polyline(&self, precision: u32) -> JsString {
+        JsString::from(self.to_polyline_string(precision))
+    }
+
+    /// decodes a path in the Encoded Polyline Algorithm Format, numbering the points from zero as
+    /// their timestamps
+    pub fn decode_polyline(encoded: &str, precision: u32) -> Result<Curve, String> {
+        let factor = 10f64.powi(precision as i32);
+        let mut values = Vec::new();
+        let mut bytes = encoded.bytes();
+
+        'values: loop {
+            let mut result: i64 = 0;
+            let mut shift = 0;
+
+            loop {
+                let byte = match bytes.next() {
+                    Some(byte) => byte,
+                    None if shift == 0 => break 'values,
+                    None => return Err("The polyline ends in the middle of a value.".to_string()),
+                };
+
+                if !(63..=126).contains(&byte) || shift > 60 {
+                    return Err("The polyline contains an invalid character.".to_string());
+                }
+
+                let chunk = (byte - 63) as i64;
+                result |= (chunk & 0x1f) << shift;
+                shift += 5;
+
+                if chunk < 0x20 {
+                    break;
+                }
+            }
+
+            values.push(if result & 1 == 1 {
+                !(result >> 1)
+            } else {
+                result >> 1
+            });
+        }
+
+        if values.len() % 2 != 0 {
+            return Err("The polyline has a latitude without a longitude.".to_string());
+        }
+
+        let (mut latitude, mut longitude) = (0, 0);
+        let pairs = values
+            .chunks(2)
+            .map(|delta| {
+                latitude += delta[0];
+                longitude += delta[1];
+                (longitude as f64 / factor, latitude as f64 / factor)
+            })
+            .collect();
+
+        Ok(Curve::from_pairs(pairs, 0.0, 1.0))
+    }
+
+    /// signed Menger curvature at every point, the inverse of the radius of the circle through the
+    /// point and its neighbours, positive for left turns
+    ///
+    /// The curvature is zero at the endpoints, at collinear points and wherever a neighbour
+    /// coincides with the point.
+    pub fn compute_signed_curvature(&self) -> Vec<f64> {
+        (0..self.len())
+            .map(|i| {
+                if i == 0 || i + 1 >= self.len() {
+                    0.0
+                } else {
+                    self.signed_curvature_at(i)
+                }
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen(js_name = compute_point_curvature_at)]
+    pub fn compute_point_curvature_at_js(&self, index: usize) -> Number {
+        Number::from(self.compute_point_curvature_at(index).unwrap_or(f64::NAN))
+    }
+
+    /// normalized autocorrelation of the y values at the lags `0..=max_lag`, one at every lag for
+    /// a constant signal
+    pub fn compute_autocorrelation(&self, max_lag: usize) -> Result<Vec<f64>, String> {
+        if max_lag >= self.len() {
+            return Err("The maximum lag must be less than the number of points.".to_string());
+        }
+
+        let n = self.len() as f64;
+        let mean = self.y.iter().sum::<f64>() / n;
+        let deviations: Vec<f64> = self.y.iter().map(|y| y - mean).collect();
+        let variance = deviations.iter().map(|d| d * d).sum::<f64>() / n;
+
+        if variance == 0.0 {
+            return Ok(vec![1.0; max_lag + 1]);
+        }
+
+        Ok((0..=max_lag)
+            .map(|lag| {
+                deviations
+                    .iter()
+                    .zip(&deviations[lag..])
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>()
+                    / (n * variance)
+            })
+            .collect())
+    }
+
+    /// rounds every x and y value to the nearest multiple of `grid_size`, keeping only the first
+    /// of any consecutive points that land on the same grid point
+    pub fn snap_to_grid(&self, grid_size: f64) -> Result<Curve, String> {
+        if !(grid_size > 0.0 && grid_size.is_finite()) {
+            return Err("The grid size must be positive and finite.".to_string());
+        }
+
+        let snap = |value: f64| (value / grid_size).round() * grid_size;
+        let mut curve = Curve {
+            timestamps: Vec::new(),
+            x: Vec::new(),
+            y: Vec::new(),
+        };
+
+        for i in 0..self.len() {
+            let (x, y) = (snap(self.x[i]), snap(self.y[i]));
+
+            if curve.x.last() == Some(&x) && curve.y.last() == Some(&y) {
+                continue;
+            }
+
+            curve.timestamps.push(self.timestamps[i]);
+            curve.x.push(x);
+            curve.y.push(y);
+        }
+
+        Ok(curve)
+    }
+
+    /// shortest distance from `(px, py)` to the path, infinite for an empty curve
+    pub fn distance_to_point(&self, px: f64, py: f64) -> f64 {
+        if self.len() == 1 {
+            return distance(px, py, self.x[0], self.y[0]);
+        }
+
+        (0..self.len().saturating_sub(1))
+            .map(|i| segment_distance(px, py, self.x[i], self.y[i], self.x[i + 1], self.y[i + 1]))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// adds `offset` to every timestamp
+    pub fn shift_timestamps(&self, offset: f64) -> Curve {
+        Curve {
+            timestamps: self.timestamps.iter().map(|t| t + offset).collect(),
+            x: self.x.clone(),
+            y: self.y.clone(),
+        }
+    }
+
+    /// multiplies every timestamp by `factor`, which must be positive to keep them in order
+    pub fn scale_timestamps(&self, factor: f64) -> Result<Curve, String> {
+        if !(factor > 0.0 && factor.is_finite()) {
+            return Err("The scale factor must be positive and finite.".to_string());
+        }
+
+        Ok(Curve {
+            timestamps: self.timestamps.iter().map(|t| t * factor).collect(),
+            x: self.x.clone(),
+            y: self.y.clone(),
+        })
+    }
+
+    /// exponentially weighted moving average of the x and y values, where `alpha` in `(0, 1]` is
+    /// the weight of the newest point
+    pub fn smooth_exponential(&self, alpha: f64) -> Result<Curve, String> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err("Alpha must be greater than zero and at most one.".to_string());
+        }
+
+        let smooth = |values: &[f64]| {
+            let mut smoothed: Vec<f64> = Vec::with_capacity(values.len());
+
+            for &value in values {
+                smoothed.push(match smoothed.last() {
+                    Some(previous) => alpha * value + (1.0 - alpha) * previous,
+                    None => value,
+                });
+            }
+
+            smoothed
+        };
+
+        Ok(Curve {
+            timestamps: self.timestamps.clone(),
+            x: smooth(&self.x),
+            y: smooth(&self.y),
+        })
+    }
+
+    #[wasm_bindgen(js_name = heading_at)]
+    pub fn heading_at_js(&self, index: usize) -> Number {
+        Number::from(self.heading_at(index).unwrap_or(f64::NAN))
+    }
+
+    /// signed change of direction at every point in radians, positive for left turns and zero at
+    /// the endpoints, a full reversal counts as a left turn of pi
+    pub fn compute_turning_angles(&self) -> Vec<f64> {
+        (0..self.len())
+            .map(|i| {
+                if i == 0 || i + 1 >= self.len() {
+                    return 0.0;
+                }
+
+                signed_turn_angle(
+                    (self.x[i - 1], self.y[i - 1]),
+                    (self.x[i], self.y[i]),
+                    (self.x[i + 1], self.y[i + 1]),
+                )
+            })
+            .collect()
+    }
+
+    /// position of every point projected onto the segment from `(x1, y1)` to `(x2, y2)`, as a
+    /// fraction of the way along it clamped to `[0, 1]`
+    pub fn project_onto_segment(&self, x1: f64, y1: f64, x2: f64, y2: f64) -> Vec<f64> {
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length_squared = dx * dx + dy * dy;
+
+        if length_squared == 0.0 {
+            return vec![0.0; self.len()];
+        }
+
+        self.x
+            .iter()
+            .zip(&self.y)
+            .map(|(x, y)| (((x - x1) * dx + (y - y1) * dy) / length_squared).clamp(0.0, 1.0))
+            .collect()
+    }
+
+    /// whether every timestamp is within `tolerance` of evenly spacing the points between the
+    /// first and last timestamps
+    pub fn timestamps_are_uniform(&self, tolerance: f64) -> bool {
+        if self.len() < 3 {
+            return true;
+        }
+
+        let start = self.timestamps[0];
+        let step = (self.timestamps[self.len() - 1] - start) / (self.len() - 1) as f64;
+
+        self.timestamps
+            .iter()
+            .enumerate()
+            .all(|(i, t)| (t - (start + i as f64 * step)).abs() <= tolerance)
+    }
+
+    /// perpendicular distance of every point from the chord between the first and last points
+    pub fn perpendicular_distances_to_chord(&self) -> Vec<f64> {
+        let (Some((_, x1, y1)), Some((_, xn, yn))) = (self.get_first(), self.get_last()) else {
+            return Vec::new();
+        };
+
+        (0..self.len())
+            .map(|i| {
+                if i == 0 || i == self.len() - 1 {
+                    0.0
+                } else {
+                    perpendicular_distance(self.x[i], self.y[i], x1, y1, xn, yn)
+                }
+            })
+            .collect()
+    }
+
+    /// repeatedly replaces adjacent interior points closer than `min_distance` with their
+    /// midpoint in time and space, the first and last points are never merged
+    pub fn merge_nearby_points(&self, min_distance: f64) -> Curve {
+        let mut curve = self.clone();
+        let mut merged = true;
+
+        while merged {
+            merged = false;
+            let mut i = 1;
+
+            while i + 2 < curve.len() {
+                if distance(curve.x[i], curve.y[i], curve.x[i + 1], curve.y[i + 1]) < min_distance {
+                    curve.timestamps[i] = (curve.timestamps[i] + curve.timestamps[i + 1]) / 2.0;
+                    curve.x[i] = (curve.x[i] + curve.x[i + 1]) / 2.0;
+                    curve.y[i] = (curve.y[i] + curve.y[i + 1]) / 2.0;
+                    curve.timestamps.remove(i + 1);
+                    curve.x.remove(i + 1);
+                    curve.y.remove(i + 1);
+                    merged = true;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+
+        curve
+    }
+
+    /// fraction of the values of `channel` in each of `bins` equal-width bins between their
+    /// minimum and maximum
+    pub fn compute_histogram(&self, bins: usize, channel: Channel) -> Result<Vec<f64>, String> {
+        if bins == 0 {
+            return Err("The number of bins must be at least one.".to_string());
+        }
+
+        if self.is_empty() {
+            return Err("Curve must contain at least one point.".to_string());
+        }
+
+        let values = self.channel(channel);
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut histogram = vec![0.0; bins];
+
+        for &value in values {
+            let bin = if max > min {
+                (((value - min) / (max - min)) * bins as f64) as usize
+            } else {
+                0
+            };
+
+            histogram[bin.min(bins - 1)] += 1.0;
+        }
+
+        Ok(histogram
+            .into_iter()
+            .map(|count| count / values.len() as f64)
+            .collect())
+    }
+
+    /// Pearson correlation of the y values of both curves, interpolated at every timestamp of
+    /// either curve where they overlap in time
+    pub fn time_series_correlation(&self, other: &Curve) -> Result<f64, String> {
+        let (Some((start, end)), Some((other_start, other_end))) =
+            (self.time_range(), other.time_range())
+        else {
+            return Err("Both curves must contain at least one point.".to_string());
+        };
+        let (start, end) = (start.max(other_start), end.min(other_end));
+
+        if start > end {
+            return Err("The curves don't overlap in time.".to_string());
+        }
+
+        let mut timestamps: Vec<f64> = self
+            .timestamps
+            .iter()
+            .chain(&other.timestamps)
+            .copied()
+            .filter(|t| (start..=end).contains(t))
+            .collect();
+        timestamps.sort_by(f64::total_cmp);
+        timestamps.dedup();
+
+        let values = |curve: &Curve| -> Vec<f64> {
+            timestamps
                 .iter()
-                .map(|&x| x as f64)
-                .collect(),
-            x: vec![
-                77, 143, 209, 461, 552, 599, 741, 821, 826, 864, 899, 912, 919,
-            ]
+                .filter_map(|&t| curve.interpolate_at_time(t))
+                .map(|(_, y)| y)
+                .collect()
+        };
+        let (a, b) = (values(self), values(other));
+        let n = a.len() as f64;
+        let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+        let mut covariance = 0.0;
+        let mut variance_a = 0.0;
+        let mut variance_b = 0.0;
+
+        for (a, b) in a.iter().zip(&b) {
+            covariance += (a - mean_a) * (b - mean_b);
+            variance_a += (a - mean_a).powi(2);
+            variance_b += (b - mean_b).powi(2);
+        }
+
+        if variance_a == 0.0 || variance_b == 0.0 {
+            return Err("The correlation of a constant signal is undefined.".to_string());
+        }
+
+        Ok((covariance / (variance_a * variance_b).sqrt()).clamp(-1.0, 1.0))
+    }
+
+    /// the curve displaced by `distance` to the left of the direction of travel, or to the right
+    /// for negative distances
+    ///
+    /// Corners use miter joins, so the offset point lies where the offset edges meet. Sharp turns
+    /// push that point far out and large distances can make the result intersect itself.
+    pub fn offset_curve(&self, distance: f64) -> Curve {
+        let mut curve = self.clone();
+
+        for (i, (nx, ny)) in self.miter_normals().into_iter().enumerate() {
+            curve.x[i] += nx * distance;
+            curve.y[i] += ny * distance;
+        }
+
+        curve
+    }
+
+    #[wasm_bindgen(js_name = to_parametric_form)]
+    pub fn to_parametric_form_js(&self) -> ParametricForm {
+        let (arc_lengths, xy) = self.to_parametric_form();
+        ParametricForm { arc_lengths, xy }
+    }
+
+    /// removes every interior point that both turns by less than `min_angle_degrees` and lies
+    /// closer than `min_distance` to the line from the previous retained point to the next point
+    pub fn simplify_by_angle_and_distance(
+        &self,
+        min_angle_degrees: f64,
+        min_distance: f64,
+    ) -> Curve {
+        if self.len() < 3 {
+            return self.clone();
+        }
+
+        let mut indices = vec![0];
+
+        for i in 1..self.len() - 1 {
+            let previous = indices[indices.len() - 1];
+            let angle = turn_angle(
+                (self.x[previous], self.y[previous]),
+                (self.x[i], self.y[i]),
+                (self.x[i + 1], self.y[i + 1]),
+            )
+            .to_degrees();
+            let d = perpendicular_distance(
+                self.x[i],
+                self.y[i],
+                self.x[previous],
+                self.y[previous],
+                self.x[i + 1],
+                self.y[i + 1],
+            );
+
+            if angle >= min_angle_degrees || d >= min_distance {
+                indices.push(i);
+            }
+        }
+
+        indices.push(self.len() - 1);
+
+        self.select(&indices)
+    }
+
+    #[wasm_bindgen(js_name = cluster_points)]
+    pub fn cluster_points_js(&self, max_distance: f64) -> Array {
+        self.cluster_points(max_distance)
             .iter()
-            .map(|&x| x as f64)
-            .collect(),
-            y: vec![
-                54, 111, 162, 336, 404, 444, 608, 680, 688, 724, 752, 776, 805,
+            .map(|cluster| indices_to_array(cluster))
+            .collect()
+    }
+
+    /// length of the path divided by its time span, `None` for curves with fewer than two points
+    pub fn compute_average_speed(&self) -> Option<f64> {
+        let span = self.time_span()?;
+        let length = self.cumulative_lengths()[self.len() - 1];
+
+        Some(length / span)
+    }
+
+    /// fastest speed over any single segment, `None` for curves with fewer than two points
+    ///
+    /// A segment between two points with the same timestamp has an infinite speed.
+    pub fn compute_max_speed(&self) -> Option<f64> {
+        self.segments()
+            .map(|((t0, x0, y0), (t1, x1, y1))| {
+                if t1 == t0 {
+                    f64::INFINITY
+                } else {
+                    distance(x0, y0, x1, y1) / (t1 - t0)
+                }
+            })
+            .reduce(f64::max)
+    }
+
+    /// the curve linearly interpolated at every one of `new_timestamps`, which must be increasing
+    /// and within the curve's time range
+    pub fn interpolate_to_timestamps(&self, new_timestamps: &[f64]) -> Result<Curve, String> {
+        if new_timestamps.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err("The timestamps must be strictly increasing.".to_string());
+        }
+
+        let mut curve = Curve {
+            timestamps: new_timestamps.to_vec(),
+            x: Vec::with_capacity(new_timestamps.len()),
+            y: Vec::with_capacity(new_timestamps.len()),
+        };
+
+        for &t in new_timestamps {
+            let (x, y) = self
+                .interpolate_at_time(t)
+                .ok_or_else(|| format!("The timestamp {} is outside the curve.", t))?;
+
+            curve.x.push(x);
+            curve.y.push(y);
+        }
+
+        Ok(curve)
+    }
+
+    /// how many times the path winds around `(px, py)`, positive for counter-clockwise and zero
+    /// outside
+    ///
+    /// This is only meaningful for closed curves, see `is_closed`.
+    pub fn compute_winding_number(&self, px: f64, py: f64) -> i32 {
+        let mut winding = 0;
+
+        for ((_, x0, y0), (_, x1, y1)) in self.segments() {
+            let side = (x1 - x0) * (py - y0) - (px - x0) * (y1 - y0);
+
+            if y0 <= py {
+                if y1 > py && side > 0.0 {
+                    winding += 1;
+                }
+            } else if y1 <= py && side < 0.0 {
+                winding -= 1;
+            }
+        }
+
+        winding
+    }
+
+    /// box-counting estimate of the fractal dimension of the path, from the boxes it passes
+    /// through at box sizes `max_scale`, `max_scale / 2` and so on down to one
+    ///
+    /// Returns NaN for an empty curve or a `max_scale` below two, which leave fewer than two
+    /// scales to fit a slope through.
+    pub fn compute_fractal_dimension(&self, max_scale: usize) -> f64 {
+        if self.is_empty() || max_scale < 2 {
+            return f64::NAN;
+        }
+
+        let points: Vec<(f64, f64)> =
+            std::iter::successors(Some(max_scale), |scale| Some(scale / 2))
+                .take_while(|&scale| scale > 0)
+                .map(|scale| {
+                    let scale = scale as f64;
+                    ((1.0 / scale).ln(), (self.count_boxes(scale) as f64).ln())
+                })
+                .collect();
+
+        // least squares slope of log(count) against log(1 / scale)
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+
+        covariance / variance
+    }
+
+    /// smooths the x and y values with a normalized Gaussian kernel of `kernel_size` points
+    ///
+    /// Near the ends the kernel is cut off and renormalized over the points that exist.
+    pub fn smooth_gaussian(&self, sigma: f64, kernel_size: usize) -> Result<Curve, String> {
+        if kernel_size < 3 || kernel_size.is_multiple_of(2) {
+            return Err("The kernel size must be odd and at least three.".to_string());
+        }
+
+        if sigma.is_nan() || sigma <= 0.0 {
+            return Err("Sigma must be positive.".to_string());
+        }
+
+        if kernel_size >= self.len() {
+            return Err("The kernel must be smaller than the curve.".to_string());
+        }
+
+        let half = (kernel_size / 2) as isize;
+        let kernel: Vec<f64> = (-half..=half)
+            .map(|offset| (-((offset * offset) as f64) / (2.0 * sigma * sigma)).exp())
+            .collect();
+
+        let smooth = |values: &[f64]| -> Vec<f64> {
+            (0..values.len() as isize)
+                .map(|i| {
+                    let (mut sum, mut weights) = (0.0, 0.0);
+
+                    for (k, weight) in kernel.iter().enumerate() {
+                        let j = i + k as isize - half;
+
+                        if let Some(value) = usize::try_from(j).ok().and_then(|j| values.get(j)) {
+                            sum += weight * value;
+                            weights += weight;
+                        }
+                    }
+
+                    sum / weights
+                })
+                .collect()
+        };
+
+        Ok(Curve {
+            timestamps: self.timestamps.clone(),
+            x: smooth(&self.x),
+            y: smooth(&self.y),
+        })
+    }
+
+    /// keeps the first point and then the first point at least `interval` after the previous
+    /// kept one, always keeping the last point
+    pub fn subsample_by_time(&self, interval: f64) -> Result<Curve, String> {
+        if interval.is_nan() || interval <= 0.0 {
+            return Err("The interval must be positive.".to_string());
+        }
+
+        if self.len() < 2 {
+            return Ok(self.clone());
+        }
+
+        let last = self.len() - 1;
+        let mut indices = vec![0];
+
+        for i in 1..last {
+            if self.timestamps[i] >= self.timestamps[indices[indices.len() - 1]] + interval {
+                indices.push(i);
+            }
+        }
+
+        indices.push(last);
+
+        Ok(self.select(&indices))
+    }
+
+    /// length of the path through all the points
+    pub fn compute_length(&self) -> f64 {
+        self.cumulative_lengths().last().copied().unwrap_or(0.0)
+    }
+
+    /// tortuosity, the length of the path divided by the distance between its first and last
+    /// points, infinite when they coincide
+    pub fn compute_length_ratio(&self) -> f64 {
+        let chord = match (self.get_first(), self.get_last()) {
+            (Some((_, x1, y1)), Some((_, x2, y2))) => distance(x1, y1, x2, y2),
+            _ => 0.0,
+        };
+
+        if chord == 0.0 {
+            return f64::INFINITY;
+        }
+
+        self.compute_length() / chord
+    }
+
+    /// the distance between the first and last points divided by the length of the path, from
+    /// one for a straight line down to zero for a closed path
+    pub fn compute_path_straightness(&self) -> f64 {
+        let ratio = self.compute_length_ratio();
+
+        if ratio.is_finite() {
+            1.0 / ratio
+        } else {
+            0.0
+        }
+    }
+
+    /// bending energy, the sum of the squared `compute_signed_curvature` values
+    pub fn compute_energy(&self) -> f64 {
+        self.compute_signed_curvature().iter().map(|k| k * k).sum()
+    }
+
+    /// builds a curve from polar `(angle, distance)` pairs around `(origin_x, origin_y)`, with the
+    /// angles in radians and the points numbered from zero as their timestamps
+    pub fn from_angle_distance(
+        angles: &[f64],
+        distances: &[f64],
+        origin_x: f64,
+        origin_y: f64,
+    ) -> Result<Curve, String> {
+        if angles.len() != distances.len() {
+            return Err("The number of angles and distances don't match.".to_string());
+        }
+
+        let pairs = angles
+            .iter()
+            .zip(distances)
+            .map(|(angle, d)| (origin_x + d * angle.cos(), origin_y + d * angle.sin()))
+            .collect();
+
+        Ok(Curve::from_pairs(pairs, 0.0, 1.0))
+    }
+
+    /// fraction of the points whose `heading_at` falls in each of `bins` equal slices of
+    /// `[0, 360)` degrees, points without a heading aren't counted
+    pub fn compute_angle_histogram(&self, bins: usize) -> Result<Vec<f64>, String> {
+        if bins == 0 {
+            return Err("The number of bins must be at least one.".to_string());
+        }
+
+        let headings: Vec<f64> = (0..self.len()).filter_map(|i| self.heading_at(i)).collect();
+        let mut histogram = vec![0.0; bins];
+
+        for heading in &headings {
+            let bin = (heading / 360.0 * bins as f64) as usize;
+            histogram[bin.min(bins - 1)] += 1.0;
+        }
+
+        Ok(histogram
+            .into_iter()
+            .map(|count| count / headings.len().max(1) as f64)
+            .collect())
+    }
+
+    /// maps every point to `(a * x + b * y + tx, c * x + d * y + ty)`
+    pub fn apply_affine_transform(
+        &self,
+        a: f64,
+        b: f64,
+        c: f64,
+        d: f64,
+        tx: f64,
+        ty: f64,
+    ) -> Curve {
+        Curve {
+            timestamps: self.timestamps.clone(),
+            x: self
+                .x
+                .iter()
+                .zip(&self.y)
+                .map(|(x, y)| a * x + b * y + tx)
+                .collect(),
+            y: self
+                .x
+                .iter()
+                .zip(&self.y)
+                .map(|(x, y)| c * x + d * y + ty)
+                .collect(),
+        }
+    }
+
+    /// rotates the curve counter-clockwise around the origin
+    pub fn rotate(&self, angle_radians: f64) -> Curve {
+        let (sin, cos) = angle_radians.sin_cos();
+        self.apply_affine_transform(cos, -sin, sin, cos, 0.0, 0.0)
+    }
+
+    /// distance from every point to the closest other point, infinite for a single point
+    pub fn compute_nearest_neighbor_distances(&self) -> Vec<f64> {
+        (0..self.len())
+            .map(|i| {
+                (0..self.len())
+                    .filter(|&j| j != i)
+                    .map(|j| distance(self.x[i], self.y[i], self.x[j], self.y[j]))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect()
+    }
+
+    /// splits every segment longer than `max_segment_length` into equal pieces with linearly
+    /// interpolated points and timestamps
+    pub fn densify(&self, max_segment_length: f64) -> Result<Curve, String> {
+        if max_segment_length.is_nan() || max_segment_length <= 0.0 {
+            return Err("The maximum segment length must be positive.".to_string());
+        }
+
+        let mut curve = Curve {
+            timestamps: Vec::with_capacity(self.len()),
+            x: Vec::with_capacity(self.len()),
+            y: Vec::with_capacity(self.len()),
+        };
+
+        for ((t0, x0, y0), (t1, x1, y1)) in self.segments() {
+            let pieces = (distance(x0, y0, x1, y1) / max_segment_length)
+                .ceil()
+                .max(1.0) as usize;
+
+            for piece in 0..pieces {
+                let fraction = piece as f64 / pieces as f64;
+                curve.timestamps.push(lerp(t0, t1, fraction));
+                curve.x.push(lerp(x0, x1, fraction));
+                curve.y.push(lerp(y0, y1, fraction));
+            }
+        }
+
+        if let Some((t, x, y)) = self.get_last() {
+            curve.timestamps.push(t);
+            curve.x.push(x);
+            curve.y.push(y);
+        }
+
+        Ok(curve)
+    }
+
+    #[wasm_bindgen(js_name = sample_at_fraction)]
+    pub fn sample_at_fraction_js(&self, fraction: f64) -> Float64Array {
+        point_to_array(self.sample_at_fraction(fraction))
+    }
+
+    /// whether the x values never decrease or never increase
+    pub fn is_monotone_in_x(&self) -> bool {
+        is_monotone(&self.x)
+    }
+
+    /// whether the y values never decrease or never increase
+    pub fn is_monotone_in_y(&self) -> bool {
+        is_monotone(&self.y)
+    }
+
+    /// every invariant the curve violates, described one per entry, empty for a well-formed curve
+    ///
+    /// Reports mismatched lengths, non-finite values, timestamps that don't increase and
+    /// consecutive points at the same position.
+    pub fn check_integrity(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        if self.timestamps.len() != self.x.len() || self.timestamps.len() != self.y.len() {
+            violations.push(format!(
+                "Timestamps, x and y have different lengths ({}, {} and {}).",
+                self.timestamps.len(),
+                self.x.len(),
+                self.y.len()
+            ));
+        }
+
+        for (name, values) in [
+            ("Timestamp", &self.timestamps),
+            ("X", &self.x),
+            ("Y", &self.y),
+        ] {
+            for (i, value) in values.iter().enumerate() {
+                if !value.is_finite() {
+                    violations.push(format!("{} at index {} is {}.", name, i, value));
+                }
+            }
+        }
+
+        for (i, pair) in self.timestamps.windows(2).enumerate() {
+            // NaN timestamps are already reported above
+            if pair[1] <= pair[0] {
+                violations.push(format!(
+                    "Timestamp at index {} ({}) does not come after the previous one ({}).",
+                    i + 1,
+                    pair[1],
+                    pair[0]
+                ));
+            }
+        }
+
+        let points = self.x.len().min(self.y.len());
+
+        for i in 1..points {
+            if self.x[i] == self.x[i - 1] && self.y[i] == self.y[i - 1] {
+                violations.push(format!(
+                    "Segment between indices {} and {} has zero length.",
+                    i - 1,
+                    i
+                ));
+            }
+        }
+
+        violations
+    }
+
+    /// appends the curve to the current path of `ctx` as a `moveTo` followed by a `lineTo` for
+    /// every other point, leaving stroking to the caller
+    pub fn to_canvas_path(&self, ctx: &CanvasRenderingContext2d) {
+        self.to_canvas_path_scaled(ctx, 1.0, 1.0, 0.0, 0.0);
+    }
+
+    /// like `to_canvas_path`, but draws every point at `(x * sx + dx, y * sy + dy)`
+    pub fn to_canvas_path_scaled(
+        &self,
+        ctx: &CanvasRenderingContext2d,
+        sx: f64,
+        sy: f64,
+        dx: f64,
+        dy: f64,
+    ) {
+        let mut points = self.x.iter().zip(&self.y);
+
+        if let Some((&x, &y)) = points.next() {
+            ctx.move_to(x * sx + dx, y * sy + dy);
+        }
+
+        for (&x, &y) in points {
+            ctx.line_to(x * sx + dx, y * sy + dy);
+        }
+    }
+
+    /// copies the curve into a `MultiChannelCurve` with `values` as its first additional channel
+    pub fn add_channel(&self, name: &str, values: Vec<f64>) -> Result<MultiChannelCurve, String> {
+        let mut curve = MultiChannelCurve {
+            curve: self.clone(),
+            channels: std::collections::HashMap::new(),
+        };
+        curve.add_channel(name, values)?;
+
+        Ok(curve)
+    }
+
+    /// the part of the path between two fractions of its arc length, starting and ending at
+    /// points interpolated like `sample_at_fraction`
+    pub fn trim(&self, start_fraction: f64, end_fraction: f64) -> Result<Curve, String> {
+        if !(0.0 <= start_fraction && start_fraction < end_fraction && end_fraction <= 1.0) {
+            return Err(
+                "The fractions must satisfy 0 <= start_fraction < end_fraction <= 1.".to_string(),
+            );
+        }
+
+        if self.len() < 2 {
+            return Err("Curve must contain at least two points.".to_string());
+        }
+
+        if start_fraction == 0.0 && end_fraction == 1.0 {
+            return Ok(self.clone());
+        }
+
+        let lengths = self.cumulative_lengths();
+        let total = lengths[lengths.len() - 1];
+        let (start, end) = (start_fraction * total, end_fraction * total);
+        let mut curve = Curve {
+            timestamps: Vec::new(),
+            x: Vec::new(),
+            y: Vec::new(),
+        };
+        let mut push = |(t, x, y): (f64, f64, f64)| {
+            curve.timestamps.push(t);
+            curve.x.push(x);
+            curve.y.push(y);
+        };
+
+        push(self.interpolate_at_length(&lengths, start));
+
+        for (i, &length) in lengths.iter().enumerate() {
+            if start < length && length < end {
+                push((self.timestamps[i], self.x[i], self.y[i]));
+            }
+        }
+
+        push(self.interpolate_at_length(&lengths, end));
+
+        Ok(curve)
+    }
+
+    /// `parallel_resample` as an array of the left and right curves
+    #[wasm_bindgen(js_name = parallel_resample)]
+    pub fn parallel_resample_js(&self, n: usize) -> Result<Array, String> {
+        let (left, right) = self.parallel_resample(n)?;
+
+        Ok(Array::of2(&left.into(), &right.into()))
+    }
+
+    /// sum of the signed turning angles in radians, including the turn back into the first
+    /// segment when the path ends where it started, so a circle traced once gives 2 pi
+    pub fn compute_total_rotation(&self) -> f64 {
+        let total: f64 = self.compute_turning_angles().iter().sum();
+        let last = self.len().saturating_sub(1);
+
+        if self.len() > 3 && self.is_closed(0.0) {
+            total
+                + signed_turn_angle(
+                    (self.x[last - 1], self.y[last - 1]),
+                    (self.x[0], self.y[0]),
+                    (self.x[1], self.y[1]),
+                )
+        } else {
+            total
+        }
+    }
+
+    /// how much of the curve survives decimation, from 0 for a path that reduces to its two
+    /// endpoints to 1 when every point is needed
+    ///
+    /// The curve is decimated with a tolerance of one percent of its bounding box diagonal and the
+    /// interior points retained are counted against the interior points of the original.
+    pub fn compute_path_complexity(&self) -> f64 {
+        if self.len() < 3 {
+            return 0.0;
+        }
+
+        match decimate_by_tolerance(
+            &self.timestamps,
+            &self.x,
+            &self.y,
+            self.diagonal_tolerance(),
+        ) {
+            Ok(decimated) => (decimated.len() - 2) as f64 / (self.len() - 2) as f64,
+            Err(_) => 0.0,
+        }
+    }
+
+    /// for every point, how many of the points after it were removed before the next retained
+    /// one, and zero for the removed points themselves
+    ///
+    /// The curve is decimated with the same tolerance as `compute_path_complexity`, so the
+    /// densities of the retained points add up to the number of points removed.
+    pub fn compute_point_density(&self) -> Vec<f64> {
+        let mut density = vec![0.0; self.len()];
+        let Ok(retained) = douglas_peucker_mask(
+            &self.timestamps,
+            &self.x,
+            &self.y,
+            self.diagonal_tolerance(),
+        ) else {
+            return density;
+        };
+
+        let mut previous = 0;
+
+        for (i, &keep) in retained.iter().enumerate().skip(1) {
+            if keep {
+                density[previous] = (i - previous - 1) as f64;
+                previous = i;
+            }
+        }
+
+        density
+    }
+
+    /// speed along every segment, infinite for segments whose timestamps are equal
+    pub fn compute_segment_velocities(&self) -> Vec<f64> {
+        (1..self.len())
+            .map(|i| {
+                let dt = self.timestamps[i] - self.timestamps[i - 1];
+                let length = distance(self.x[i - 1], self.y[i - 1], self.x[i], self.y[i]);
+
+                if dt == 0.0 {
+                    f64::INFINITY
+                } else {
+                    length / dt
+                }
+            })
+            .collect()
+    }
+
+    /// change of speed between consecutive segments, divided by the time between their midpoints
+    pub fn compute_segment_accelerations(&self) -> Vec<f64> {
+        let velocities = self.compute_segment_velocities();
+
+        (1..velocities.len())
+            .map(|i| {
+                let dt = (self.timestamps[i + 1] - self.timestamps[i - 1]) / 2.0;
+                (velocities[i] - velocities[i - 1]) / dt
+            })
+            .collect()
+    }
+
+    /// change of bearing at every point in degrees within `(-180, 180]`, positive for turns to
+    /// the right (clockwise), zero at the endpoints and next to segments without length
+    pub fn compute_bearing_changes(&self) -> Vec<f64> {
+        (0..self.len())
+            .map(|i| {
+                if i == 0 || i + 1 >= self.len() {
+                    return 0.0;
+                }
+
+                let (ax, ay) = (self.x[i] - self.x[i - 1], self.y[i] - self.y[i - 1]);
+                let (bx, by) = (self.x[i + 1] - self.x[i], self.y[i + 1] - self.y[i]);
+
+                if (ax == 0.0 && ay == 0.0) || (bx == 0.0 && by == 0.0) {
+                    return 0.0;
+                }
+
+                let change = bearing(bx, by) - bearing(ax, ay);
+
+                if change <= -180.0 {
+                    change + 360.0
+                } else if change > 180.0 {
+                    change - 360.0
+                } else {
+                    change
+                }
+            })
+            .collect()
+    }
+
+    /// replaces every run of points that moves less than `min_movement` along the path over more
+    /// than `min_duration` with the first and last point of the run, as when a vehicle is parked
+    pub fn prune_stationary_segments(&self, min_movement: f64, min_duration: f64) -> Curve {
+        let lengths = self.cumulative_lengths();
+        let mut kept = Vec::with_capacity(self.len());
+        let mut start = 0;
+        let mut end = 0;
+
+        while start < self.len() {
+            // the longest run from `start` that stays under the movement limit
+            end = end.max(start);
+            while end + 1 < self.len() && lengths[end + 1] - lengths[start] < min_movement {
+                end += 1;
+            }
+
+            kept.push(start);
+
+            if end > start + 1 && self.timestamps[end] - self.timestamps[start] > min_duration {
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+
+        self.select(&kept)
+    }
+
+    /// the largest distance between any two points, comparing every pair, so best suited to
+    /// decimated curves
+    pub fn compute_spatial_extent(&self) -> f64 {
+        let mut diameter: f64 = 0.0;
+
+        for i in 0..self.len() {
+            for j in (i + 1)..self.len() {
+                diameter = diameter.max(distance(self.x[i], self.y[i], self.x[j], self.y[j]));
+            }
+        }
+
+        diameter
+    }
+
+    /// decimates the curve by removing points in reverse Ramer-Douglas-Peucker order, least
+    /// significant first, and undoing every removal that moves the enclosed area more than
+    /// `max_area_loss_fraction` of the original area away from it, with both areas closed by a
+    /// segment back to the first point
+    pub fn simplify_preserving_area(&self, max_area_loss_fraction: f64) -> Curve {
+        if self.len() < 3 {
+            return self.clone();
+        }
+
+        let last = self.len() - 1;
+        // twice the signed area contributed by the edge from `i` to `j`
+        let cross = |i: usize, j: usize| self.x[i] * self.y[j] - self.x[j] * self.y[i];
+        let original: f64 = (0..last).map(|i| cross(i, i + 1)).sum::<f64>() + cross(last, 0);
+        let allowed = (original * max_area_loss_fraction).abs();
+
+        // distances are never negative, so their bit patterns order the same way they do
+        let mut heap = std::collections::BinaryHeap::new();
+        let push_segment = |heap: &mut std::collections::BinaryHeap<_>, start, end| {
+            if let Some(i) = farthest_point(&self.x, &self.y, start, end, f64::NEG_INFINITY) {
+                let d = perpendicular_distance(
+                    self.x[i],
+                    self.y[i],
+                    self.x[start],
+                    self.y[start],
+                    self.x[end],
+                    self.y[end],
+                );
+                heap.push((d.to_bits(), i, start, end));
+            }
+        };
+        push_segment(&mut heap, 0, last);
+
+        let mut order = Vec::with_capacity(last - 1);
+
+        while let Some((_, i, start, end)) = heap.pop() {
+            order.push(i);
+            push_segment(&mut heap, start, i);
+            push_segment(&mut heap, i, end);
+        }
+
+        let mut previous: Vec<usize> = (0..self.len()).map(|i| i.saturating_sub(1)).collect();
+        let mut next: Vec<usize> = (1..=self.len()).collect();
+        let mut kept = vec![true; self.len()];
+        let mut area = original;
+
+        for &i in order.iter().rev() {
+            let (before, after) = (previous[i], next[i]);
+            let removed = area - cross(before, i) - cross(i, after) + cross(before, after);
+
+            if (removed - original).abs() > allowed {
+                continue;
+            }
+
+            area = removed;
+            kept[i] = false;
+            next[before] = after;
+            previous[after] = before;
+        }
+
+        let indices: Vec<usize> = (0..self.len()).filter(|&i| kept[i]).collect();
+
+        self.select(&indices)
+    }
+
+    /// encodes the curve as a little-endian Well-Known Binary `LineString Z`, with the timestamps
+    /// as the z coordinates
+    pub fn encode_wkb(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(9 + self.len() * 24);
+
+        bytes.push(1);
+        bytes.extend_from_slice(&WKB_LINE_STRING_Z.to_le_bytes());
+        bytes.extend_from_slice(&(self.len() as u32).to_le_bytes());
+        for i in 0..self.len() {
+            for value in [self.x[i], self.y[i], self.timestamps[i]] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// decodes a Well-Known Binary `LineString` in either byte order, taking the timestamps from
+    /// the z coordinates of a `LineString Z` and numbering the points from zero otherwise
+    pub fn decode_wkb(bytes: &[u8]) -> Result<Curve, String> {
+        let little_endian = match bytes.first() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err("The data does not start with a WKB byte order marker.".to_string()),
+        };
+        let read_u32 = |offset: usize| -> Option<u32> {
+            let word = bytes.get(offset..offset + 4)?.try_into().unwrap();
+            Some(if little_endian {
+                u32::from_le_bytes(word)
+            } else {
+                u32::from_be_bytes(word)
+            })
+        };
+        let read_f64 = |offset: usize| {
+            let word = bytes[offset..offset + 8].try_into().unwrap();
+            if little_endian {
+                f64::from_le_bytes(word)
+            } else {
+                f64::from_be_bytes(word)
+            }
+        };
+
+        let dimensions = match read_u32(1) {
+            Some(WKB_LINE_STRING) => 2,
+            Some(WKB_LINE_STRING_Z) => 3,
+            Some(kind) => return Err(format!("WKB geometry type {} is not a LineString.", kind)),
+            None => return Err("The WKB data is too short.".to_string()),
+        };
+        let count = read_u32(5).ok_or("The WKB data is too short.")? as usize;
+
+        let expected = count
+            .checked_mul(dimensions * 8)
+            .ok_or_else(|| format!("The point count {} is too large.", count))?;
+
+        if bytes.len() - 9 != expected {
+            return Err(format!(
+                "Expected {} bytes of point data for {} points, found {}.",
+                expected,
+                count,
+                bytes.len() - 9
+            ));
+        }
+
+        let mut curve = Curve {
+            timestamps: Vec::with_capacity(count),
+            x: Vec::with_capacity(count),
+            y: Vec::with_capacity(count),
+        };
+
+        for i in 0..count {
+            let offset = 9 + i * dimensions * 8;
+            curve.x.push(read_f64(offset));
+            curve.y.push(read_f64(offset + 8));
+            curve.timestamps.push(if dimensions == 3 {
+                read_f64(offset + 16)
+            } else {
+                i as f64
+            });
+        }
+
+        Ok(curve)
+    }
+
+    /// `render_to_pixels` as a `Uint8ClampedArray` ready for `ImageData`
+    pub fn render_to_pixel_buffer(&self, width: u32, height: u32, color: u32) -> Uint8ClampedArray {
+        Uint8ClampedArray::from(&self.render_to_pixels(width, height, color)[..])
+    }
+
+    /// `find_loops` as an array of `[start, end]` index arrays
+    #[wasm_bindgen(js_name = find_loops)]
+    pub fn find_loops_js(&self, min_loop_area: f64) -> Array {
+        self.find_loops(min_loop_area)
+            .into_iter()
+            .map(|(start, end)| Array::of2(&(start as u32).into(), &(end as u32).into()))
+            .collect()
+    }
+
+    /// timestamps of the interior points whose y value is above both neighbours
+    pub fn compute_local_maxima_timestamps(&self) -> Vec<f64> {
+        (1..self.len().saturating_sub(1))
+            .filter(|&i| self.y[i] > self.y[i - 1] && self.y[i] > self.y[i + 1])
+            .map(|i| self.timestamps[i])
+            .collect()
+    }
+
+    /// timestamps of the interior points whose y value is below both neighbours
+    pub fn compute_local_minima_timestamps(&self) -> Vec<f64> {
+        (1..self.len().saturating_sub(1))
+            .filter(|&i| self.y[i] < self.y[i - 1] && self.y[i] < self.y[i + 1])
+            .map(|i| self.timestamps[i])
+            .collect()
+    }
+
+    /// population standard deviation of the distances of the interior points to the chords
+    /// between their neighbours, zero for curves without interior points
+    ///
+    /// Low values mean tight tolerances work well, high values suggest smoothing first.
+    pub fn estimate_noise_level(&self) -> f64 {
+        let deviations = self.local_deviations();
+
+        if deviations.is_empty() {
+            return 0.0;
+        }
+
+        let n = deviations.len() as f64;
+        let mean = deviations.iter().sum::<f64>() / n;
+
+        (deviations.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n).sqrt()
+    }
+
+    /// timestamps at which the signed curvature changes sign, interpolated linearly between the
+    /// two points with curvature of opposite signs around it and skipping collinear points
+    pub fn find_inflection_timestamps(&self) -> Vec<f64> {
+        let curvature = self.compute_signed_curvature();
+        let mut inflections = Vec::new();
+        let mut previous: Option<usize> = None;
+
+        for (i, &k) in curvature.iter().enumerate() {
+            if k == 0.0 {
+                continue;
+            }
+
+            if let Some(p) = previous {
+                if (curvature[p] > 0.0) != (k > 0.0) {
+                    let fraction = curvature[p] / (curvature[p] - k);
+                    inflections.push(lerp(self.timestamps[p], self.timestamps[i], fraction));
+                }
+            }
+
+            previous = Some(i);
+        }
+
+        inflections
+    }
+
+    /// arc length over which the direction of travel stays correlated, infinite when the
+    /// correlation never falls off, as for a straight line
+    ///
+    /// The correlation at a lag of `k` segments is the mean dot product of every increment with
+    /// the increment `k` segments later, relative to the mean squared increment. The first lag at
+    /// which it falls below `1/e`, interpolated linearly, is scaled by the mean segment length.
+    pub fn compute_autocorrelation_length(&self) -> f64 {
+        let increments: Vec<(f64, f64)> = (1..self.len())
+            .map(|i| (self.x[i] - self.x[i - 1], self.y[i] - self.y[i - 1]))
+            .collect();
+        let n = increments.len();
+        let power = increments
+            .iter()
+            .map(|(dx, dy)| dx * dx + dy * dy)
+            .sum::<f64>()
+            / n as f64;
+
+        if n < 2 || power == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let mean_length = increments.iter().map(|(dx, dy)| dx.hypot(*dy)).sum::<f64>() / n as f64;
+        let threshold = (-1.0f64).exp();
+        let mut previous = 1.0;
+
+        for lag in 1..n {
+            let correlation = increments
+                .iter()
+                .zip(&increments[lag..])
+                .map(|(a, b)| a.0 * b.0 + a.1 * b.1)
+                .sum::<f64>()
+                / ((n - lag) as f64 * power);
+
+            if correlation < threshold {
+                let fraction = (previous - threshold) / (previous - correlation);
+                return (lag as f64 - 1.0 + fraction) * mean_length;
+            }
+
+            previous = correlation;
+        }
+
+        f64::INFINITY
+    }
+
+    /// the curve with its timestamps rescaled so that it starts at 0 and ends at 1, every
+    /// timestamp becomes 0 when the curve has no duration
+    pub fn to_duration_normalized(&self) -> Curve {
+        let (start, end) = self.time_range().unwrap_or((0.0, 0.0));
+        let duration = end - start;
+
+        Curve {
+            timestamps: self
+                .timestamps
+                .iter()
+                .map(|&t| {
+                    if duration == 0.0 {
+                        0.0
+                    } else {
+                        (t - start) / duration
+                    }
+                })
+                .collect(),
+            x: self.x.clone(),
+            y: self.y.clone(),
+        }
+    }
+
+    /// decimates the curve with `decimate_by_tolerance` in both directions of travel and keeps
+    /// only the points retained by both, so the selection doesn't depend on the direction
+    pub fn apply_ramer_douglas_peucker_bidirectional(
+        &self,
+        tolerance: f64,
+    ) -> Result<Curve, String> {
+        let reversed = self.reversed();
+        let forward = douglas_peucker_mask(&self.timestamps, &self.x, &self.y, tolerance)?;
+        let backward =
+            douglas_peucker_mask(&reversed.timestamps, &reversed.x, &reversed.y, tolerance)?;
+        let mask: Vec<bool> = forward
+            .iter()
+            .zip(backward.iter().rev())
+            .map(|(&a, &b)| a && b)
+            .collect();
+
+        combine_with_mask(&self.timestamps, &self.x, &self.y, &mask)
+    }
+
+    /// bearing of every segment in degrees clockwise from the positive y axis, in `[0, 360)`,
+    /// zero for segments without length
+    pub fn compute_segment_angles(&self) -> Vec<f64> {
+        (1..self.len())
+            .map(|i| bearing(self.x[i] - self.x[i - 1], self.y[i] - self.y[i - 1]))
+            .collect()
+    }
+
+    /// replaces every x and y value with the median of the `window` values centred on it,
+    /// narrowing the window symmetrically near the ends, which removes isolated spikes while
+    /// keeping edges sharp
+    pub fn smooth_median(&self, window: usize) -> Result<Curve, String> {
+        if window.is_multiple_of(2) {
+            return Err("The window size must be odd.".to_string());
+        }
+
+        let half = window / 2;
+        let smooth = |values: &[f64]| -> Vec<f64> {
+            let mut neighbourhood = Vec::with_capacity(window);
+
+            (0..values.len())
+                .map(|i| {
+                    let reach = half.min(i).min(values.len() - 1 - i);
+                    neighbourhood.clear();
+                    neighbourhood.extend_from_slice(&values[(i - reach)..=(i + reach)]);
+                    neighbourhood.sort_by(f64::total_cmp);
+                    neighbourhood[reach]
+                })
+                .collect()
+        };
+
+        Ok(Curve {
+            timestamps: self.timestamps.clone(),
+            x: smooth(&self.x),
+            y: smooth(&self.y),
+        })
+    }
+
+    /// splits the curve wherever x changes between increasing and decreasing, so that every part
+    /// is monotone in x, with each extremum of x both ending one part and starting the next
+    pub fn split_into_monotone_x_segments(&self) -> Vec<Curve> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut boundaries = vec![0];
+        let mut direction = 0.0;
+
+        for i in 1..self.len() {
+            if self.x[i] == self.x[i - 1] {
+                continue;
+            }
+
+            let step = (self.x[i] - self.x[i - 1]).signum();
+
+            if direction != 0.0 && step != direction {
+                boundaries.push(i - 1);
+            }
+
+            direction = step;
+        }
+
+        boundaries.push(self.len() - 1);
+
+        boundaries
+            .windows(2)
+            .map(|pair| self.select(&(pair[0]..=pair[1]).collect::<Vec<_>>()))
+            .collect()
+    }
+
+    /// encodes the curve as the protobuf message
+    /// `message Curve { repeated double timestamps = 1; repeated double x = 2; repeated double y = 3; }`
+    /// with the packed encoding a protobuf library uses for repeated doubles
+    pub fn encode_protobuf(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len() * 24 + 18);
+
+        for (field, values) in [(1, &self.timestamps), (2, &self.x), (3, &self.y)] {
+            // empty repeated fields are left out entirely
+            if values.is_empty() {
+                continue;
+            }
+
+            write_varint(&mut bytes, field << 3 | 2);
+            write_varint(&mut bytes, values.len() as u64 * 8);
+            for value in values {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// decodes the message written by `encode_protobuf`, also accepting unpacked doubles and
+    /// skipping unknown fields like a protobuf library would
+    pub fn decode_protobuf(bytes: &[u8]) -> Result<Curve, String> {
+        let mut channels: [Vec<f64>; 3] = Default::default();
+        let mut position = 0;
+        let too_short = || "The protobuf data ends in the middle of a field.".to_string();
+
+        while position < bytes.len() {
+            let key = read_varint(bytes, &mut position)?;
+            let channel = match key >> 3 {
+                field @ 1..=3 => Some(&mut channels[field as usize - 1]),
+                _ => None,
+            };
+
+            match key & 7 {
+                0 => {
+                    read_varint(bytes, &mut position)?;
+                }
+                1 => {
+                    let word = bytes.get(position..position + 8).ok_or_else(too_short)?;
+                    if let Some(channel) = channel {
+                        channel.push(f64::from_le_bytes(word.try_into().unwrap()));
+                    }
+                    position += 8;
+                }
+                2 => {
+                    let length = read_varint(bytes, &mut position)? as usize;
+                    let data = position
+                        .checked_add(length)
+                        .and_then(|end| bytes.get(position..end))
+                        .ok_or_else(too_short)?;
+                    if let Some(channel) = channel {
+                        if !length.is_multiple_of(8) {
+                            return Err("A packed double field has a partial value.".to_string());
+                        }
+                        channel.extend(
+                            data.chunks_exact(8)
+                                .map(|word| f64::from_le_bytes(word.try_into().unwrap())),
+                        );
+                    }
+                    position += length;
+                }
+                5 => {
+                    bytes.get(position..position + 4).ok_or_else(too_short)?;
+                    position += 4;
+                }
+                wire_type => return Err(format!("Unsupported protobuf wire type {}.", wire_type)),
+            }
+        }
+
+        let [timestamps, x, y] = channels;
+        Curve::from_vectors(timestamps, x, y)
+    }
+
+    /// fills every gap of more than `max_gap` between consecutive timestamps with points every
+    /// `max_gap` after the start of the gap, interpolated linearly, and returns the curve unchanged
+    /// unless `max_gap` is positive and finite
+    pub fn interpolate_missing(&self, max_gap: f64) -> Curve {
+        if !(max_gap > 0.0 && max_gap.is_finite()) {
+            return self.clone();
+        }
+
+        let mut curve = Curve {
+            timestamps: Vec::with_capacity(self.len()),
+            x: Vec::with_capacity(self.len()),
+            y: Vec::with_capacity(self.len()),
+        };
+
+        for i in 0..self.len() {
+            if i > 0 {
+                let (start, end) = (self.timestamps[i - 1], self.timestamps[i]);
+                let mut step = 1.0;
+
+                while start + step * max_gap < end {
+                    let fraction = step * max_gap / (end - start);
+                    curve.timestamps.push(start + step * max_gap);
+                    curve.x.push(lerp(self.x[i - 1], self.x[i], fraction));
+                    curve.y.push(lerp(self.y[i - 1], self.y[i], fraction));
+                    step += 1.0;
+                }
+            }
+
+            curve.timestamps.push(self.timestamps[i]);
+            curve.x.push(self.x[i]);
+            curve.y.push(self.y[i]);
+        }
+
+        curve
+    }
+
+    /// circular mean of the segment bearings in degrees within `[0, 360)`, ignoring segments
+    /// without length, `None` without any such segment or when the bearings cancel out
+    pub fn compute_average_heading(&self) -> Option<f64> {
+        let (mut sin, mut cos) = (0.0, 0.0);
+
+        for i in 1..self.len() {
+            let (dx, dy) = (self.x[i] - self.x[i - 1], self.y[i] - self.y[i - 1]);
+
+            if dx != 0.0 || dy != 0.0 {
+                let angle = bearing(dx, dy).to_radians();
+                sin += angle.sin();
+                cos += angle.cos();
+            }
+        }
+
+        if sin.hypot(cos) < f64::EPSILON {
+            return None;
+        }
+
+        // a bearing is measured from the y axis, so its sine plays the part of dx
+        Some(bearing(sin, cos))
+    }
+
+    /// area enclosed by the path closed with a segment back to its first point, by the shoelace
+    /// formula, zero for fewer than three points, parts that wind in opposite directions cancel
+    pub fn compute_area(&self) -> f64 {
+        let n = self.len();
+
+        if n < 3 {
+            return 0.0;
+        }
+
+        let twice_area: f64 = (0..n)
+            .map(|i| {
+                let j = (i + 1) % n;
+                self.x[i] * self.y[j] - self.x[j] * self.y[i]
+            })
+            .sum();
+
+        twice_area.abs() / 2.0
+    }
+
+    /// area of the convex hull, zero when the points are collinear or there are fewer than three
+    pub fn compute_convex_hull_area(&self) -> f64 {
+        self.compute_convex_hull().compute_area()
+    }
+
+    /// `douglas_peucker_with_epsilon_schedule` on this curve, a curve with fewer than two points
+    /// is returned unchanged for every tolerance
+    pub fn douglas_peucker_with_epsilon_schedule(&self, epsilons: &[f64]) -> Vec<Curve> {
+        douglas_peucker_with_epsilon_schedule(&self.timestamps, &self.x, &self.y, epsilons)
+            .unwrap_or_else(|_| vec![self.clone(); epsilons.len()])
+    }
+
+    /// `apply_douglas_peucker_recursive` on this curve
+    pub fn apply_douglas_peucker_recursive(
+        &self,
+        tolerance: f64,
+        recursion_limit: usize,
+    ) -> Result<Curve, String> {
+        apply_douglas_peucker_recursive(
+            &self.timestamps,
+            &self.x,
+            &self.y,
+            tolerance,
+            recursion_limit,
+        )
+    }
+}
+
+impl Curve {
+    /// returns `(timestamp, x, y)` for the point at `index`
+    pub fn get_point(&self, index: usize) -> Option<(f64, f64, f64)> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some((self.timestamps[index], self.x[index], self.y[index]))
+    }
+
+    pub fn get_first(&self) -> Option<(f64, f64, f64)> {
+        self.get_point(0)
+    }
+
+    pub fn get_last(&self) -> Option<(f64, f64, f64)> {
+        self.get_point(self.len().checked_sub(1)?)
+    }
+
+    /// combines two curves sampled at the same timestamps point by point using `f(x1, y1, x2, y2)`
+    pub fn zip_with(
+        &self,
+        other: &Curve,
+        f: impl Fn(f64, f64, f64, f64) -> (f64, f64),
+    ) -> Result<Curve, String> {
+        if self.timestamps != other.timestamps {
+            return Err("The timestamps of both curves must match.".to_string());
+        }
+
+        let mut curve = Curve {
+            timestamps: self.timestamps.clone(),
+            x: Vec::with_capacity(self.len()),
+            y: Vec::with_capacity(self.len()),
+        };
+
+        for i in 0..self.len() {
+            let (x, y) = f(self.x[i], self.y[i], other.x[i], other.y[i]);
+            curve.x.push(x);
+            curve.y.push(y);
+        }
+
+        Ok(curve)
+    }
+
+    pub fn from_vectors(timestamps: Vec<f64>, x: Vec<f64>, y: Vec<f64>) -> Result<Curve, String> {
+        check_lengths(&timestamps, &x, &y)?;
+
+        Ok(Curve { timestamps, x, y })
+    }
+
+    /// returns `(x_min, x_max, y_min, y_max)`
+    fn bounding_box(&self) -> (f64, f64, f64, f64) {
+        let x_min = self.x.iter().copied().fold(f64::INFINITY, f64::min);
+        let x_max = self.x.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let y_min = self.y.iter().copied().fold(f64::INFINITY, f64::min);
+        let y_max = self.y.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        (x_min, x_max, y_min, y_max)
+    }
+
+    /// arithmetic mean of the coordinates, `(NaN, NaN)` for an empty curve
+    pub fn centroid(&self) -> (f64, f64) {
+        let n = self.len() as f64;
+
+        (
+            self.x.iter().sum::<f64>() / n,
+            self.y.iter().sum::<f64>() / n,
+        )
+    }
+
+    /// `(dx/dt, dy/dt)` at every point, using one-sided differences at the ends and central differences elsewhere
+    pub fn compute_derivatives(&self) -> (Vec<f64>, Vec<f64>) {
+        (
+            derivative(&self.timestamps, &self.x),
+            derivative(&self.timestamps, &self.y),
+        )
+    }
+
+    /// serializes the curve as the magic bytes, a little-endian `u32` point count and then the
+    /// timestamps, x and y values as little-endian `f64`s
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.len() * 24);
+
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.extend_from_slice(&(self.len() as u32).to_le_bytes());
+        for value in self.timestamps.iter().chain(&self.x).chain(&self.y) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Curve, String> {
+        if bytes.len() < 8 || &bytes[..4] != BINARY_MAGIC {
+            return Err("The data is not a binary curve.".to_string());
+        }
+
+        let count = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        let expected = count
+            .checked_mul(24)
+            .ok_or_else(|| format!("The point count {} is too large.", count))?;
+        if bytes.len() - 8 != expected {
+            return Err(format!(
+                "Expected {} bytes of point data for {} points, found {}.",
+                expected,
+                count,
+                bytes.len() - 8
+            ));
+        }
+
+        let mut values = bytes[8..]
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()));
+
+        Ok(Curve {
+            timestamps: values.by_ref().take(count).collect(),
+            x: values.by_ref().take(count).collect(),
+            y: values.collect(),
+        })
+    }
+
+    pub fn from_base64(encoded: &str) -> Result<Curve, String> {
+        Curve::from_binary(&base64_decode(encoded)?)
+    }
+
+    /// indices of the interior local maxima of `y` whose prominence, the height above the higher of
+    /// the lowest points on either side before reaching a higher point, exceeds `min_prominence`
+    pub fn peak_detection(&self, min_prominence: f64) -> Vec<usize> {
+        let y = &self.y;
+        let mut peaks = Vec::new();
+
+        for i in 1..y.len().saturating_sub(1) {
+            if y[i] <= y[i - 1] || y[i] <= y[i + 1] {
+                continue;
+            }
+
+            let left_min = y[..i]
+                .iter()
+                .rev()
+                .take_while(|&&value| value <= y[i])
+                .copied()
+                .fold(f64::INFINITY, f64::min);
+            let right_min = y[i + 1..]
+                .iter()
+                .take_while(|&&value| value <= y[i])
+                .copied()
+                .fold(f64::INFINITY, f64::min);
+
+            if y[i] - left_min.max(right_min) > min_prominence {
+                peaks.push(i);
+            }
+        }
+
+        peaks
+    }
+
+    fn channel(&self, channel: Channel) -> &[f64] {
+        match channel {
+            Channel::X => &self.x,
+            Channel::Y => &self.y,
+        }
+    }
+
+    /// indices of the points just before the channel crosses `threshold` in either direction,
+    /// values equal to the threshold count as being above it
+    pub fn threshold_crossings(&self, threshold: f64, channel: Channel) -> Vec<usize> {
+        let values = self.channel(channel);
+
+        (0..values.len().saturating_sub(1))
+            .filter(|&i| (values[i] < threshold) != (values[i + 1] < threshold))
+            .collect()
+    }
+
+    pub fn rising_crossings(&self, threshold: f64, channel: Channel) -> Vec<usize> {
+        let values = self.channel(channel);
+
+        self.threshold_crossings(threshold, channel)
+            .into_iter()
+            .filter(|&i| values[i] < threshold)
+            .collect()
+    }
+
+    pub fn falling_crossings(&self, threshold: f64, channel: Channel) -> Vec<usize> {
+        let values = self.channel(channel);
+
+        self.threshold_crossings(threshold, channel)
+            .into_iter()
+            .filter(|&i| values[i] >= threshold)
+            .collect()
+    }
+
+    /// serializes the curve as `{"t":[...],"x":[...],"y":[...]}`, non-finite values are written as `null`
+    pub fn to_json_string(&self) -> String {
+        let array = |values: &[f64]| {
+            values
+                .iter()
+                .map(|value| {
+                    if value.is_finite() {
+                        value.to_string()
+                    } else {
+                        "null".to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        format!(
+            "{{\"t\":[{}],\"x\":[{}],\"y\":[{}]}}",
+            array(&self.timestamps),
+            array(&self.x),
+            array(&self.y)
+        )
+    }
+
+    /// parses the format written by `to_json_string`, `null` values are read as NaN
+    pub fn from_json_str(json: &str) -> Result<Curve, String> {
+        let mut parser = JsonParser {
+            bytes: json.as_bytes(),
+            position: 0,
+        };
+        let (mut timestamps, mut x, mut y) = (None, None, None);
+
+        parser.expect(b'{')?;
+        loop {
+            let key = parser.parse_key()?;
+            parser.expect(b':')?;
+            let values = parser.parse_array()?;
+
+            let channel = match key {
+                "t" => &mut timestamps,
+                "x" => &mut x,
+                "y" => &mut y,
+                _ => return Err(format!("Unexpected key \"{}\" in the JSON curve.", key)),
+            };
+            if channel.replace(values).is_some() {
+                return Err(format!("Duplicate key \"{}\" in the JSON curve.", key));
+            }
+
+            if !parser.consume(b',') {
+                break;
+            }
+        }
+        parser.expect(b'}')?;
+        parser.expect_end()?;
+
+        match (timestamps, x, y) {
+            (Some(timestamps), Some(x), Some(y)) => Curve::from_vectors(timestamps, x, y),
+            _ => Err("The JSON curve must contain the keys \"t\", \"x\" and \"y\".".to_string()),
+        }
+    }
+
+    /// `(x, y)` of every point
+    pub fn to_pairs(&self) -> Vec<(f64, f64)> {
+        self.x.iter().copied().zip(self.y.iter().copied()).collect()
+    }
+
+    /// `(timestamp, x, y)` of every point
+    pub fn to_triples(&self) -> Vec<(f64, f64, f64)> {
+        (0..self.len())
+            .map(|i| (self.timestamps[i], self.x[i], self.y[i]))
+            .collect()
+    }
+
+    /// builds a curve from `(x, y)` pairs sampled every `step` starting at `start_time`
+    pub fn from_pairs(pairs: Vec<(f64, f64)>, start_time: f64, step: f64) -> Curve {
+        let (x, y): (Vec<f64>, Vec<f64>) = pairs.into_iter().unzip();
+
+        Curve {
+            timestamps: (0..x.len()).map(|i| start_time + i as f64 * step).collect(),
+            x,
+            y,
+        }
+    }
+
+    /// builds a curve of `n` points evenly spaced in x from `x_start` to `x_end` with
+    /// `y = y_func(x)`, using the x values as timestamps, handy for generating test data
+    pub fn create_uniform_from_range(
+        x_start: f64,
+        x_end: f64,
+        y_func: impl Fn(f64) -> f64,
+        n: usize,
+    ) -> Curve {
+        let x: Vec<f64> = (0..n)
+            .map(|i| match n {
+                1 => x_start,
+                _ => lerp(x_start, x_end, i as f64 / (n - 1) as f64),
+            })
+            .collect();
+
+        Curve {
+            timestamps: x.clone(),
+            y: x.iter().map(|&x| y_func(x)).collect(),
+            x,
+        }
+    }
+
+    /// builds a curve from `(timestamp, x, y)` triples
+    pub fn from_triples(triples: Vec<(f64, f64, f64)>) -> Curve {
+        Curve::from_timestamped_points(&triples)
+    }
+
+    /// builds a curve from borrowed `(timestamp, x, y)` points in a single pass
+    pub fn from_timestamped_points(points: &[(f64, f64, f64)]) -> Curve {
+        let mut curve = Curve {
+            timestamps: Vec::with_capacity(points.len()),
+            x: Vec::with_capacity(points.len()),
+            y: Vec::with_capacity(points.len()),
+        };
+
+        for &(t, x, y) in points {
+            curve.timestamps.push(t);
+            curve.x.push(x);
+            curve.y.push(y);
+        }
+
+        curve
+    }
+
+    /// samples `f(t) -> (x, y)` at `n` evenly spaced timestamps from `t_start` to `t_end`
+    pub fn from_timestamp_fn(
+        n: usize,
+        t_start: f64,
+        t_end: f64,
+        f: impl Fn(f64) -> (f64, f64),
+    ) -> Curve {
+        let step = if n > 1 {
+            (t_end - t_start) / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        let timestamps: Vec<f64> = (0..n).map(|i| t_start + i as f64 * step).collect();
+        let (x, y) = timestamps.iter().map(|&t| f(t)).unzip();
+
+        Curve { timestamps, x, y }
+    }
+
+    /// distance of every interior point to the chord between its two neighbours
+    fn local_deviations(&self) -> Vec<f64> {
+        (1..self.len().saturating_sub(1))
+            .map(|i| {
+                perpendicular_distance(
+                    self.x[i],
+                    self.y[i],
+                    self.x[i - 1],
+                    self.y[i - 1],
+                    self.x[i + 1],
+                    self.y[i + 1],
+                )
+            })
+            .collect()
+    }
+
+    /// a header and one line per point, writing `non_finite` in place of NaN and infinite values
+    pub fn to_delimited_string(&self, delimiter: &str, non_finite: &str) -> String {
+        let field = |value: f64| {
+            if value.is_finite() {
+                value.to_string()
+            } else {
+                non_finite.to_string()
+            }
+        };
+        let mut output = format!("timestamps{0}x{0}y\n", delimiter);
+
+        for i in 0..self.x.len() {
+            output.push_str(&format!(
+                "{}{3}{}{3}{}\n",
+                field(self.timestamps[i]),
+                field(self.x[i]),
+                field(self.y[i]),
+                delimiter
+            ))
+        }
+
+        output
+    }
+
+    /// the points as a text table with a header and right-aligned columns
+    fn to_table_string(&self) -> String {
+        let header = ["t", "x", "y"].map(String::from);
+        let rows: Vec<[String; 3]> = (0..self.len())
+            .map(|i| [self.timestamps[i], self.x[i], self.y[i]].map(|value| value.to_string()))
+            .collect();
+        let widths: Vec<usize> = (0..3)
+            .map(|column| {
+                rows.iter()
+                    .chain([&header])
+                    .map(|row| row[column].len())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |row: &[String; 3]| {
+            let fields: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(field, &width)| format!("{:>1$}", field, width))
+                .collect();
+            fields.join(" | ")
+        };
+        let separator: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        let mut output = format!("{}\n{}\n", line(&header), separator.join("-+-"));
+
+        for row in &rows {
+            output.push_str(&line(row));
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// the points at the given indices, in the given order
+    fn select(&self, indices: &[usize]) -> Curve {
+        Curve {
+            timestamps: indices.iter().map(|&i| self.timestamps[i]).collect(),
+            x: indices.iter().map(|&i| self.x[i]).collect(),
+            y: indices.iter().map(|&i| self.y[i]).collect(),
+        }
+    }
+
+    /// distance along the path from the first point to every point
+    fn cumulative_lengths(&self) -> Vec<f64> {
+        let mut lengths = Vec::with_capacity(self.len());
+        let mut total = 0.0;
+
+        for i in 0..self.len() {
+            if i > 0 {
+                total += distance(self.x[i - 1], self.y[i - 1], self.x[i], self.y[i]);
+            }
+            lengths.push(total);
+        }
+
+        lengths
+    }
+
+    /// linearly interpolates `(timestamp, x, y)` at the distance `length` along the path, where
+    /// `lengths` comes from `cumulative_lengths`
+    fn interpolate_at_length(&self, lengths: &[f64], length: f64) -> (f64, f64, f64) {
+        // the first segment that ends at or after the requested length
+        let i = lengths[1..]
+            .partition_point(|&l| l < length)
+            .min(self.len() - 2);
+        let segment = lengths[i + 1] - lengths[i];
+        let fraction = if segment > 0.0 {
+            ((length - lengths[i]) / segment).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        (
+            lerp(self.timestamps[i], self.timestamps[i + 1], fraction),
+            lerp(self.x[i], self.x[i + 1], fraction),
+            lerp(self.y[i], self.y[i + 1], fraction),
+        )
+    }
+
+    /// intersection points between non-adjacent segments, stopping after the first one if
+    /// `first_only` is set
+    fn self_intersections(&self, first_only: bool) -> Vec<(f64, f64)> {
+        let mut intersections = Vec::new();
+        let segments = self.len().saturating_sub(1);
+        let closed = self.len() > 2
+            && self.x[0] == self.x[self.len() - 1]
+            && self.y[0] == self.y[self.len() - 1];
+
+        for i in 0..segments {
+            for j in (i + 2)..segments {
+                // the first and last segments of a closed path share their endpoint
+                if closed && i == 0 && j == segments - 1 {
+                    continue;
+                }
+
+                if let Some(point) = segment_intersection(
+                    (self.x[i], self.y[i]),
+                    (self.x[i + 1], self.y[i + 1]),
+                    (self.x[j], self.y[j]),
+                    (self.x[j + 1], self.y[j + 1]),
+                ) {
+                    intersections.push(point);
+
+                    if first_only {
+                        return intersections;
+                    }
+                }
+            }
+        }
+
+        intersections
+    }
+
+    /// iterator over the pairs of consecutive points
+    pub fn segments(&self) -> CurveSegmentIter<'_> {
+        CurveSegmentIter {
+            curve: self,
+            index: 0,
+        }
+    }
+
+    /// iterator over every run of `size` consecutive points as `(timestamps, x, y)` slices, like
+    /// `slice::windows`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is less than two.
+    pub fn window_iter(&self, size: usize) -> CurveWindowIter<'_> {
+        assert!(size >= 2, "The window size must be at least two.");
+
+        CurveWindowIter {
+            curve: self,
+            size,
+            index: 0,
+        }
+    }
+
+    /// the points at the given indices, in increasing index order regardless of the order they
+    /// are given in
+    pub fn sample(&self, indices: &[usize]) -> Result<Curve, String> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+
+        if let Some(&index) = indices.last().filter(|&&i| i >= self.len()) {
+            return Err(format!(
+                "Index {} is out of bounds for a curve with {} points.",
+                index,
+                self.len()
+            ));
+        }
+
+        Ok(self.select(&indices))
+    }
+
+    /// the first and last timestamps
+    pub fn time_range(&self) -> Option<(f64, f64)> {
+        Some((*self.timestamps.first()?, *self.timestamps.last()?))
+    }
+
+    /// indices of the convex hull vertices in counter-clockwise order, using Andrew's monotone
+    /// chain algorithm
+    fn convex_hull_indices(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by(|&a, &b| {
+            self.x[a]
+                .total_cmp(&self.x[b])
+                .then(self.y[a].total_cmp(&self.y[b]))
+        });
+        order.dedup_by(|a, b| self.x[*a] == self.x[*b] && self.y[*a] == self.y[*b]);
+
+        if order.len() < 3 {
+            return order;
+        }
+
+        let turns_left = |a: usize, b: usize, c: usize| {
+            (self.x[b] - self.x[a]) * (self.y[c] - self.y[a])
+                - (self.y[b] - self.y[a]) * (self.x[c] - self.x[a])
+                > 0.0
+        };
+        let mut hull: Vec<usize> = Vec::with_capacity(order.len() + 1);
+
+        // lower hull from left to right
+        for &i in &order {
+            while hull.len() >= 2 && !turns_left(hull[hull.len() - 2], hull[hull.len() - 1], i) {
+                hull.pop();
+            }
+            hull.push(i);
+        }
+
+        // upper hull from right to left
+        let lower_len = hull.len() + 1;
+
+        for &i in order.iter().rev().skip(1) {
+            while hull.len() >= lower_len
+                && !turns_left(hull[hull.len() - 2], hull[hull.len() - 1], i)
+            {
+                hull.pop();
+            }
+            hull.push(i);
+        }
+
+        // the leftmost point closes the upper hull
+        hull.pop();
+
+        hull
+    }
+
+    /// the string behind `encode_polyline`
+    pub fn to_polyline_string(&self, precision: u32) -> String {
+        let factor = 10f64.powi(precision as i32);
+        let mut output = String::new();
+        let (mut latitude, mut longitude) = (0, 0);
+
+        let mut encode = |value: i64| {
+            let mut value = if value < 0 { !(value << 1) } else { value << 1 };
+
+            while value >= 0x20 {
+                output.push((((value & 0x1f) | 0x20) as u8 + 63) as char);
+                value >>= 5;
+            }
+
+            output.push((value as u8 + 63) as char);
+        };
+
+        for i in 0..self.len() {
+            let y = (self.y[i] * factor).round() as i64;
+            let x = (self.x[i] * factor).round() as i64;
+
+            encode(y - latitude);
+            encode(x - longitude);
+
+            latitude = y;
+            longitude = x;
+        }
+
+        output
+    }
+
+    /// direction of motion at the point in degrees clockwise from north (the positive y axis),
+    /// taken from its neighbours, `None` if the point doesn't exist or its neighbours coincide
+    pub fn heading_at(&self, index: usize) -> Option<f64> {
+        if index >= self.len() || self.len() < 2 {
+            return None;
+        }
+
+        let previous = index.saturating_sub(1);
+        let next = (index + 1).min(self.len() - 1);
+        let (dx, dy) = (
+            self.x[next] - self.x[previous],
+            self.y[next] - self.y[previous],
+        );
+
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+
+        Some(bearing(dx, dy))
+    }
+
+    /// the x and y values without the timestamps, which `from_pairs` can rebuild for curves where
+    /// `timestamps_are_uniform`
+    pub fn strip_timestamps(self) -> (Vec<f64>, Vec<f64>) {
+        (self.x, self.y)
+    }
+
+    /// linearly interpolated `(x, y)` at `timestamp`, `None` outside the curve's time range
+    fn interpolate_at_time(&self, timestamp: f64) -> Option<(f64, f64)> {
+        let (start, end) = self.time_range()?;
+
+        if !(start..=end).contains(&timestamp) {
+            return None;
+        }
+
+        let i = self.timestamps.partition_point(|&t| t < timestamp);
+
+        if self.timestamps[i] == timestamp {
+            return Some((self.x[i], self.y[i]));
+        }
+
+        let fraction =
+            (timestamp - self.timestamps[i - 1]) / (self.timestamps[i] - self.timestamps[i - 1]);
+
+        Some((
+            lerp(self.x[i - 1], self.x[i], fraction),
+            lerp(self.y[i - 1], self.y[i], fraction),
+        ))
+    }
+
+    /// the distance along the path to every point and the points as interleaved `[x0, y0, x1,
+    /// y1, ...]` values
+    pub fn to_parametric_form(&self) -> (Vec<f64>, Vec<f64>) {
+        let xy = self
+            .x
+            .iter()
+            .zip(&self.y)
+            .flat_map(|(&x, &y)| [x, y])
+            .collect();
+
+        (self.cumulative_lengths(), xy)
+    }
+
+    /// single-linkage clusters of point indices, joining any two points closer than
+    /// `max_distance`, ordered by their first index
+    pub fn cluster_points(&self, max_distance: f64) -> Vec<Vec<usize>> {
+        let mut clustered = vec![false; self.len()];
+        let mut clusters = Vec::new();
+
+        for seed in 0..self.len() {
+            if clustered[seed] {
+                continue;
+            }
+
+            let mut members = vec![seed];
+            clustered[seed] = true;
+            let mut next = 0;
+
+            while next < members.len() {
+                let i = members[next];
+                next += 1;
+
+                for (j, clustered) in clustered.iter_mut().enumerate() {
+                    if !*clustered
+                        && distance(self.x[i], self.y[i], self.x[j], self.y[j]) < max_distance
+                    {
+                        *clustered = true;
+                        members.push(j);
+                    }
+                }
+            }
+
+            members.sort_unstable();
+            clusters.push(members);
+        }
+
+        clusters
+    }
+
+    /// raw pointers to the timestamps, x and y values, and the number of points behind each
+    ///
+    /// # Safety
+    ///
+    /// The pointers are only valid for as long as the curve is borrowed and must not be used
+    /// after it's modified or dropped. The values must only be read through them.
+    pub unsafe fn as_ptr_triple(&self) -> (*const f64, *const f64, *const f64, usize) {
+        (
+            self.timestamps.as_ptr(),
+            self.x.as_ptr(),
+            self.y.as_ptr(),
+            self.len(),
+        )
+    }
+
+    /// one percent of the bounding box diagonal, a tolerance that keeps the overall shape
+    fn diagonal_tolerance(&self) -> f64 {
+        let (x_min, x_max, y_min, y_max) = self.bounding_box();
+        (x_max - x_min).hypot(y_max - y_min) * 0.01
+    }
+
+    /// number of `scale` sized grid boxes that the path passes through
+    fn count_boxes(&self, scale: f64) -> usize {
+        let mut boxes = std::collections::HashSet::new();
+        let mut mark = |x: f64, y: f64| {
+            boxes.insert(((x / scale).floor() as i64, (y / scale).floor() as i64));
+        };
+
+        mark(self.x[0], self.y[0]);
+
+        for ((_, x0, y0), (_, x1, y1)) in self.segments() {
+            // sample the segment finely enough not to skip over any box
+            let steps = (distance(x0, y0, x1, y1) / scale * 2.0).ceil().max(1.0) as usize;
+
+            for step in 1..=steps {
+                let fraction = step as f64 / steps as f64;
+                mark(lerp(x0, x1, fraction), lerp(y0, y1, fraction));
+            }
+        }
+
+        boxes.len()
+    }
+
+    /// the `(timestamp, x, y)` point `fraction` of the way along the path, `None` for an empty
+    /// curve or a fraction outside `[0, 1]`
+    pub fn sample_at_fraction(&self, fraction: f64) -> Option<(f64, f64, f64)> {
+        if self.is_empty() || !(0.0..=1.0).contains(&fraction) {
+            return None;
+        }
+
+        if self.len() == 1 || fraction == 1.0 {
+            return self.get_last();
+        }
+
+        let lengths = self.cumulative_lengths();
+
+        Some(self.interpolate_at_length(&lengths, fraction * lengths[lengths.len() - 1]))
+    }
+
+    /// the unit normal to the left of the direction of travel at every point, scaled at corners
+    /// so that offsetting along it gives a miter join
+    fn miter_normals(&self) -> Vec<(f64, f64)> {
+        let normal = |i: usize| -> Option<(f64, f64)> {
+            let (dx, dy) = (self.x[i + 1] - self.x[i], self.y[i + 1] - self.y[i]);
+            let length = dx.hypot(dy);
+
+            (length > 0.0).then(|| (-dy / length, dx / length))
+        };
+
+        (0..self.len())
+            .map(|i| {
+                let incoming = if i > 0 { normal(i - 1) } else { None };
+                let outgoing = if i + 1 < self.len() { normal(i) } else { None };
+
+                match (incoming, outgoing) {
+                    (Some(a), Some(b)) => {
+                        let (mx, my) = (a.0 + b.0, a.1 + b.1);
+                        let length = mx.hypot(my);
+
+                        if length < f64::EPSILON {
+                            // the path doubles back on itself
+                            a
+                        } else {
+                            let (mx, my) = (mx / length, my / length);
+                            let scale = 1.0 / (mx * a.0 + my * a.1);
+                            (mx * scale, my * scale)
+                        }
+                    }
+                    (Some(n), None) | (None, Some(n)) => n,
+                    (None, None) => (0.0, 0.0),
+                }
+            })
+            .collect()
+    }
+
+    /// resamples the curve to `n` points evenly spaced by arc length, then offsets it by one unit
+    /// to the left and to the right of the direction of travel, as for the two sides of a road
+    pub fn parallel_resample(&self, n: usize) -> Result<(Curve, Curve), String> {
+        let center = self.resample_by_arc_length(n)?;
+        let mut left = center.clone();
+        let mut right = center.clone();
+
+        for (i, (nx, ny)) in center.miter_normals().into_iter().enumerate() {
+            left.x[i] += nx;
+            left.y[i] += ny;
+            right.x[i] -= nx;
+            right.y[i] -= ny;
+        }
+
+        Ok((left, right))
+    }
+
+    /// rasterizes the path into a `width` by `height` RGBA buffer, row by row from the top, with
+    /// the bounding box stretched over the whole image and y pointing up
+    ///
+    /// Segments are drawn anti-aliased with Xiaolin Wu's algorithm in the packed `0xRRGGBBAA`
+    /// `color` on a transparent background. Where segments overlap, a pixel keeps the highest
+    /// coverage rather than blending.
+    pub fn render_to_pixels(&self, width: u32, height: u32, color: u32) -> Vec<u8> {
+        let (width, height) = (width as usize, height as usize);
+        let mut pixels = vec![0u8; width * height * 4];
+        let [red, green, blue, alpha] = color.to_be_bytes();
+        let normalized = self.normalize();
+        let point = |i: usize| {
+            (
+                normalized.x[i] * (width as f64 - 1.0),
+                (1.0 - normalized.y[i]) * (height as f64 - 1.0),
+            )
+        };
+        let mut plot = |column: i64, row: i64, coverage: f64| {
+            if column < 0 || row < 0 || column as usize >= width || row as usize >= height {
+                return;
+            }
+
+            let offset = (row as usize * width + column as usize) * 4;
+            let value = (alpha as f64 * coverage).round() as u8;
+
+            if value > pixels[offset + 3] {
+                pixels[offset..offset + 4].copy_from_slice(&[red, green, blue, value]);
+            }
+        };
+
+        for i in 1..self.len() {
+            let ((x0, y0), (x1, y1)) = (point(i - 1), point(i));
+            draw_wu_line(x0, y0, x1, y1, &mut plot);
+        }
+
+        pixels
+    }
+
+    /// `(start, end)` index pairs where the path comes back to within one mean segment length of
+    /// an earlier point after enclosing more than `min_loop_area`
+    ///
+    /// The enclosed area is that of the sub-curve `start..=end` closed back on itself. Loops don't
+    /// overlap, the search for the next one begins where the previous one ended.
+    pub fn find_loops(&self, min_loop_area: f64) -> Vec<(usize, usize)> {
+        let mut loops = Vec::new();
+
+        if self.len() < 4 {
+            return loops;
+        }
+
+        let threshold = self.compute_length() / (self.len() - 1) as f64;
+        let mut start = 0;
+
+        'starts: while start + 3 < self.len() {
+            // twice the signed area of the sub-curve so far, without its closing edge
+            let mut area = 0.0;
+
+            for end in (start + 1)..self.len() {
+                area += self.x[end - 1] * self.y[end] - self.x[end] * self.y[end - 1];
+
+                let closing = self.x[end] * self.y[start] - self.x[start] * self.y[end];
+
+                if end > start + 2
+                    && distance(self.x[start], self.y[start], self.x[end], self.y[end]) < threshold
+                    && (area + closing).abs() / 2.0 > min_loop_area
+                {
+                    loops.push((start, end));
+                    start = end;
+                    continue 'starts;
+                }
+            }
+
+            start += 1;
+        }
+
+        loops
+    }
+
+    /// signed Menger curvature at the interior point `i`, see `compute_signed_curvature`
+    fn signed_curvature_at(&self, i: usize) -> f64 {
+        let (ax, ay) = (self.x[i] - self.x[i - 1], self.y[i] - self.y[i - 1]);
+        let (bx, by) = (self.x[i + 1] - self.x[i], self.y[i + 1] - self.y[i]);
+        let incoming = ax.hypot(ay);
+        let outgoing = bx.hypot(by);
+        let cross = ax * by - ay * bx;
+
+        if incoming == 0.0 || outgoing == 0.0 || cross.abs() < f64::EPSILON * incoming * outgoing {
+            return 0.0;
+        }
+
+        let chord = distance(self.x[i - 1], self.y[i - 1], self.x[i + 1], self.y[i + 1]);
+        2.0 * cross / (incoming * outgoing * chord)
+    }
+
+    /// the signed curvature at `index` from that point and its two neighbours alone, `None` at
+    /// the endpoints and past the end
+    pub fn compute_point_curvature_at(&self, index: usize) -> Option<f64> {
+        (index > 0 && index + 1 < self.len()).then(|| self.signed_curvature_at(index))
+    }
+}
+
+/// selects a coordinate channel of a curve
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    X,
+    Y,
+}
+
+/// summary of the values in each channel of a curve, variances are population variances
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveStatistics {
+    #[wasm_bindgen(readonly)]
+    pub x_mean: f64,
+    #[wasm_bindgen(readonly)]
+    pub x_variance: f64,
+    #[wasm_bindgen(readonly)]
+    pub x_min: f64,
+    #[wasm_bindgen(readonly)]
+    pub x_max: f64,
+    #[wasm_bindgen(readonly)]
+    pub y_mean: f64,
+    #[wasm_bindgen(readonly)]
+    pub y_variance: f64,
+    #[wasm_bindgen(readonly)]
+    pub y_min: f64,
+    #[wasm_bindgen(readonly)]
+    pub y_max: f64,
+    #[wasm_bindgen(readonly)]
+    pub t_min: f64,
+    #[wasm_bindgen(readonly)]
+    pub t_max: f64,
+    #[wasm_bindgen(readonly)]
+    pub point_count: usize,
+}
+
+/// how far the points of an original curve are from its decimated version, see
+/// `quality_report`
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecimationReport {
+    #[wasm_bindgen(readonly)]
+    pub max_error: f64,
+    #[wasm_bindgen(readonly)]
+    pub mean_error: f64,
+    #[wasm_bindgen(readonly)]
+    pub error_stddev: f64,
+    #[wasm_bindgen(readonly)]
+    pub max_error_at_timestamp: f64,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct CurveDerivatives {
+    dx_dt: Vec<f64>,
+    dy_dt: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl CurveDerivatives {
+    pub fn get_dx_dt(&self) -> Float64Array {
+        Float64Array::from(&self.dx_dt[..])
+    }
+
+    pub fn get_dy_dt(&self) -> Float64Array {
+        Float64Array::from(&self.dy_dt[..])
+    }
+}
+
+/// a curve parameterized by arc length, see `Curve::to_parametric_form`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ParametricForm {
+    arc_lengths: Vec<f64>,
+    xy: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl ParametricForm {
+    pub fn get_arc_lengths(&self) -> Float64Array {
+        Float64Array::from(&self.arc_lengths[..])
+    }
+
+    pub fn get_xy(&self) -> Float64Array {
+        Float64Array::from(&self.xy[..])
+    }
+}
+
+/// a curve with additional named channels of values, one per point, such as speed or signal
+/// strength, see `Curve::add_channel`
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct MultiChannelCurve {
+    curve: Curve,
+    channels: std::collections::HashMap<String, Vec<f64>>,
+}
+
+#[wasm_bindgen]
+impl MultiChannelCurve {
+    /// adds or replaces the channel called `name`, which must have a value for every point
+    pub fn add_channel(&mut self, name: &str, values: Vec<f64>) -> Result<(), String> {
+        if values.len() != self.curve.len() {
+            return Err("The channel must have a value for every point.".to_string());
+        }
+
+        self.channels.insert(name.to_string(), values);
+
+        Ok(())
+    }
+
+    pub fn get_channel(&self, name: &str) -> Option<Float64Array> {
+        self.channel(name).map(Float64Array::from)
+    }
+
+    pub fn get_curve(&self) -> Curve {
+        self.curve.clone()
+    }
+
+    /// the names of the additional channels in alphabetical order
+    pub fn channel_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.channels.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+impl MultiChannelCurve {
+    pub fn channel(&self, name: &str) -> Option<&[f64]> {
+        self.channels.get(name).map(Vec::as_slice)
+    }
+}
+
+/// iterator over the `((t0, x0, y0), (t1, x1, y1))` pairs of consecutive points of a curve
+#[derive(Debug, Clone)]
+pub struct CurveSegmentIter<'a> {
+    curve: &'a Curve,
+    index: usize,
+}
+
+impl Iterator for CurveSegmentIter<'_> {
+    type Item = ((f64, f64, f64), (f64, f64, f64));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.curve.get_point(self.index)?;
+        let end = self.curve.get_point(self.index + 1)?;
+
+        self.index += 1;
+
+        Some((start, end))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.curve.len().saturating_sub(self.index + 1);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for CurveSegmentIter<'_> {}
+
+/// iterator over overlapping `(timestamps, x, y)` windows of a curve
+#[derive(Debug, Clone)]
+pub struct CurveWindowIter<'a> {
+    curve: &'a Curve,
+    size: usize,
+    index: usize,
+}
+
+impl<'a> Iterator for CurveWindowIter<'a> {
+    type Item = (&'a [f64], &'a [f64], &'a [f64]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.index..self.index + self.size;
+
+        if range.end > self.curve.len() {
+            return None;
+        }
+
+        self.index += 1;
+
+        Some((
+            &self.curve.timestamps[range.clone()],
+            &self.curve.x[range.clone()],
+            &self.curve.y[range],
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.curve.len() + 1).saturating_sub(self.index + self.size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for CurveWindowIter<'_> {}
+
+/// a curve of at most `N` points stored inline, for use without heap allocation
+#[derive(Debug, Clone, Copy)]
+pub struct FixedCapacityCurve<const N: usize> {
+    timestamps: [f64; N],
+    x: [f64; N],
+    y: [f64; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedCapacityCurve<N> {
+    pub fn new() -> Self {
+        FixedCapacityCurve {
+            timestamps: [0.0; N],
+            x: [0.0; N],
+            y: [0.0; N],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get_point(&self, index: usize) -> Option<(f64, f64, f64)> {
+        if index >= self.len {
+            return None;
+        }
+
+        Some((self.timestamps[index], self.x[index], self.y[index]))
+    }
+
+    /// appends a point after the last one
+    pub fn push(&mut self, timestamp: f64, x: f64, y: f64) -> Result<(), String> {
+        self.insert(self.len, timestamp, x, y)
+    }
+
+    /// inserts a point before `index`, keeping the timestamps strictly increasing
+    pub fn insert(&mut self, index: usize, timestamp: f64, x: f64, y: f64) -> Result<(), String> {
+        if index > self.len {
+            return Err(format!(
+                "Index {} is out of bounds for a curve with {} points.",
+                index, self.len
+            ));
+        }
+
+        if self.len == N {
+            return Err(format!(
+                "The curve is already at its capacity of {} points.",
+                N
+            ));
+        }
+
+        if (index > 0 && timestamp <= self.timestamps[index - 1])
+            || (index < self.len && timestamp >= self.timestamps[index])
+        {
+            return Err(format!(
+                "Inserting timestamp {} at index {} would break the order of the timestamps.",
+                timestamp, index
+            ));
+        }
+
+        self.timestamps.copy_within(index..self.len, index + 1);
+        self.x.copy_within(index..self.len, index + 1);
+        self.y.copy_within(index..self.len, index + 1);
+        self.timestamps[index] = timestamp;
+        self.x[index] = x;
+        self.y[index] = y;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    pub fn to_curve(&self) -> Curve {
+        Curve {
+            timestamps: self.timestamps[..self.len].to_vec(),
+            x: self.x[..self.len].to_vec(),
+            y: self.y[..self.len].to_vec(),
+        }
+    }
+}
+
+impl<const N: usize> Default for FixedCapacityCurve<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// limits for the binary search over tolerances in `decimate_to_count_with_options`
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchOptions {
+    pub max_iterations: usize,
+    /// searched from instead of zero
+    pub initial_lower_limit: Option<f64>,
+    /// searched up to instead of the longest step between consecutive points
+    pub initial_upper_limit: Option<f64>,
+}
+
+#[wasm_bindgen]
+impl SearchOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        max_iterations: usize,
+        initial_upper_limit: Option<f64>,
+        initial_lower_limit: Option<f64>,
+    ) -> SearchOptions {
+        SearchOptions {
+            max_iterations,
+            initial_lower_limit,
+            initial_upper_limit,
+        }
+    }
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions::new(MAX_BINARY_SEARCH_ITERATIONS, None, None)
+    }
+}
+
+/// how the distance of a point from the line between the `start` and `end` points is measured
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// distance to the closest point of the segment between `start` and `end`
+    Euclidean,
+    /// distance to the infinite line through `start` and `end`
+    PerpendicularProjection,
+    /// perpendicular distance with the timestamps treated as a third dimension
+    TimeWeighted,
+}
+
+impl DistanceMetric {
+    fn distance(
+        self,
+        timestamps: &[f64],
+        x: &[f64],
+        y: &[f64],
+        i: usize,
+        start: usize,
+        end: usize,
+    ) -> f64 {
+        match self {
+            DistanceMetric::Euclidean => {
+                segment_distance(x[i], y[i], x[start], y[start], x[end], y[end])
+            }
+            DistanceMetric::PerpendicularProjection => {
+                perpendicular_distance(x[i], y[i], x[start], y[start], x[end], y[end])
+            }
+            DistanceMetric::TimeWeighted => perpendicular_distance_3d(
+                (timestamps[i], x[i], y[i]),
+                (timestamps[start], x[start], y[start]),
+                (timestamps[end], x[end], y[end]),
+            ),
+        }
+    }
+
+    /// length of the step from point `i` to point `i + 1`
+    fn step_length(self, timestamps: &[f64], x: &[f64], y: &[f64], i: usize) -> f64 {
+        let d = distance(x[i], y[i], x[i + 1], y[i + 1]);
+
+        match self {
+            DistanceMetric::TimeWeighted => d.hypot(timestamps[i + 1] - timestamps[i]),
+            _ => d,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn decimate_by_tolerance(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+) -> Result<Curve, String> {
+    decimate_by_tolerance_with_metric(
+        timestamps,
+        x,
+        y,
+        tolerance,
+        DistanceMetric::PerpendicularProjection,
+    )
+}
+
+#[wasm_bindgen]
+pub fn decimate_by_tolerance_with_metric(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+    metric: DistanceMetric,
+) -> Result<Curve, String> {
+    decimate_by_distance(timestamps, x, y, tolerance, |i, start, end| {
+        metric.distance(timestamps, x, y, i, start, end)
+    })
+}
+
+/// like `decimate_by_tolerance` but with the distance of every point multiplied by its weight,
+/// points with an infinite weight are always kept
+#[wasm_bindgen]
+pub fn decimate_by_weighted_tolerance(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    weights: &[f64],
+    tolerance: f64,
+) -> Result<Curve, String> {
+    if weights.len() != timestamps.len() {
+        return Err("The weights must have the same length as the curve.".to_string());
+    }
+
+    decimate_by_distance(timestamps, x, y, tolerance, |i, start, end| {
+        if weights[i] == f64::INFINITY {
+            return f64::INFINITY;
+        }
+
+        weights[i] * perpendicular_distance(x[i], y[i], x[start], y[start], x[end], y[end])
+    })
+}
+
+/// the Ramer-Douglas-Peucker algorithm, with `distance(i, start, end)` measuring how far point
+/// `i` is from the line between the `start` and `end` points
+fn decimate_by_distance(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+    distance: impl Fn(usize, usize, usize) -> f64,
+) -> Result<Curve, String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let mut decimated_curve = Curve {
+        timestamps: vec![timestamps[0], timestamps[timestamps.len() - 1]],
+        x: vec![x[0], x[x.len() - 1]],
+        y: vec![y[0], y[y.len() - 1]],
+    };
+    let mut stack: Vec<(usize, usize)> = vec![(0, timestamps.len() - 1)];
+
+    while let Some((start, end)) = stack.pop() {
+        if end - start < 2 {
+            continue;
+        }
+
+        let mut dmax = distance(start + 1, start, end);
+        let mut dmax_is_at = start + 1;
+
+        for i in (start + 2)..(end) {
+            let d = distance(i, start, end);
+
+            if d > dmax {
+                dmax = d;
+                dmax_is_at = i;
+            }
+        }
+
+        if dmax > tolerance {
+            // find the end value's index in the final curve and insert this element just before it
+            let index = decimated_curve
+                .timestamps
+                .iter()
+                .position(|&i| i == timestamps[end])
+                .expect("The 'end' value should already exist in the final curve.");
+            decimated_curve.insert(index, timestamps[dmax_is_at], x[dmax_is_at], y[dmax_is_at])?;
+
+            stack.push((start, dmax_is_at));
+            stack.push((dmax_is_at, end));
+        }
+    }
+
+    Ok(decimated_curve)
+}
+
+#[wasm_bindgen]
+pub fn decimate_to_count(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+) -> Result<Curve, String> {
+    decimate_to_count_with_tolerance(timestamps, x, y, count).map(|(curve, _)| curve)
+}
+
+/// like `decimate_to_count`, but also returns the tolerance that produced the curve
+pub fn decimate_to_count_with_tolerance(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+) -> Result<(Curve, f64), String> {
+    decimate_to_count_with_search(
+        timestamps,
+        x,
+        y,
+        count..=count,
+        DistanceMetric::PerpendicularProjection,
+        &SearchOptions::default(),
+    )
+}
+
+/// decimates the curve to as many points as fit in `max_bytes`, assuming each point takes 24
+/// bytes for its three `f64`s
+///
+/// The size of the `Vec`s themselves and of any JavaScript wrappers isn't accounted for.
+#[wasm_bindgen]
+pub fn decimate_to_memory_budget(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    max_bytes: usize,
+) -> Result<Curve, String> {
+    let max_points = max_bytes / 24;
+
+    if max_points < 2 {
+        return Err("The budget must fit at least two points (48 bytes).".to_string());
+    }
+
+    decimate_to_count(timestamps, x, y, max_points)
+}
+
+/// decimates the curve to any number of points between `min_count` and `max_count`, inclusive
+#[wasm_bindgen]
+pub fn decimate_to_count_range(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    min_count: usize,
+    max_count: usize,
+) -> Result<Curve, String> {
+    decimate_to_count_with_search(
+        timestamps,
+        x,
+        y,
+        min_count..=max_count,
+        DistanceMetric::PerpendicularProjection,
+        &SearchOptions::default(),
+    )
+    .map(|(curve, _)| curve)
+}
+
+#[wasm_bindgen]
+pub fn decimate_to_count_with_metric(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+    metric: DistanceMetric,
+) -> Result<Curve, String> {
+    decimate_to_count_with_search(
+        timestamps,
+        x,
+        y,
+        count..=count,
+        metric,
+        &SearchOptions::default(),
+    )
+    .map(|(curve, _)| curve)
+}
+
+/// decimates the curve to exactly `count` points, with control over the tolerance search
+#[wasm_bindgen]
+pub fn decimate_to_count_with_options(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+    options: &SearchOptions,
+) -> Result<Curve, String> {
+    decimate_to_count_with_search(
+        timestamps,
+        x,
+        y,
+        count..=count,
+        DistanceMetric::PerpendicularProjection,
+        options,
+    )
+    .map(|(curve, _)| curve)
+}
+
+fn decimate_to_count_with_search(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    counts: std::ops::RangeInclusive<usize>,
+    metric: DistanceMetric,
+    options: &SearchOptions,
+) -> Result<(Curve, f64), String> {
+    check_count(timestamps, x, y, *counts.start())?;
+
+    if counts.is_empty() {
+        return Err("The minimum count must not exceed the maximum count.".to_string());
+    }
+
+    let mut max_distance = metric.step_length(timestamps, x, y, 0);
+
+    for i in 1..(x.len() - 1) {
+        max_distance = max_distance.max(metric.step_length(timestamps, x, y, i));
+    }
+
+    search_tolerance(
+        counts,
+        max_distance,
+        options,
+        timestamps.len(),
+        |tolerance| decimate_by_tolerance_with_metric(timestamps, x, y, tolerance, metric),
+    )
+}
+
+/// decimates the curve at every tolerance in a single pass, returning the curves in order of
+/// increasing tolerance
+///
+/// Every point is given the tolerance below which the algorithm would keep it, so coarser curves
+/// are always subsets of finer ones.
+#[wasm_bindgen]
+pub fn douglas_peucker_with_epsilon_schedule(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerances: &[f64],
+) -> Result<Vec<Curve>, String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let n = timestamps.len();
+    // points no segment splits at, like those behind a NaN distance, are never kept
+    let mut importance = vec![f64::NEG_INFINITY; n];
+    importance[0] = f64::INFINITY;
+    importance[n - 1] = f64::INFINITY;
+    let mut stack: Vec<(usize, usize)> = vec![(0, n - 1)];
+
+    while let Some((start, end)) = stack.pop() {
+        let Some(index) = farthest_point(x, y, start, end, f64::NEG_INFINITY) else {
+            continue;
+        };
+        let d = perpendicular_distance(x[index], y[index], x[start], y[start], x[end], y[end]);
+
+        // a point is only reached once both points of its segment have been kept
+        importance[index] = d.min(importance[start]).min(importance[end]);
+
+        stack.push((start, index));
+        stack.push((index, end));
+    }
+
+    let mut tolerances = tolerances.to_vec();
+    tolerances.sort_by(f64::total_cmp);
+
+    let curve = Curve {
+        timestamps: timestamps.to_vec(),
+        x: x.to_vec(),
+        y: y.to_vec(),
+    };
+
+    Ok(tolerances
+        .iter()
+        .map(|&tolerance| {
+            let indices: Vec<usize> = (0..n).filter(|&i| importance[i] > tolerance).collect();
+            curve.select(&indices)
+        })
+        .collect())
+}
+
+/// `decimate_by_tolerance` writing into `output` without allocating, fails if the result needs
+/// more than `N` points
+pub fn decimate_by_tolerance_fixed<const N: usize>(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+    output: &mut FixedCapacityCurve<N>,
+) -> Result<(), String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let last = timestamps.len() - 1;
+    *output = FixedCapacityCurve::new();
+    output.push(timestamps[0], x[0], y[0])?;
+    output.push(timestamps[last], x[last], y[last])?;
+
+    // every retained point adds at most one segment to the stack, so `N` entries are enough
+    let mut stack = [(0, 0); N];
+    stack[0] = (0, last);
+    let mut stack_len = 1;
+
+    while stack_len > 0 {
+        stack_len -= 1;
+        let (start, end) = stack[stack_len];
+
+        if let Some(dmax_is_at) = farthest_point(x, y, start, end, tolerance) {
+            let index = (0..output.len())
+                .position(|i| output.timestamps[i] == timestamps[end])
+                .expect("The 'end' value should already exist in the final curve.");
+            output.insert(index, timestamps[dmax_is_at], x[dmax_is_at], y[dmax_is_at])?;
+
+            stack[stack_len] = (start, dmax_is_at);
+            stack[stack_len + 1] = (dmax_is_at, end);
+            stack_len += 2;
+        }
+    }
+
+    Ok(())
+}
+
+/// recursive reference version of `decimate_by_tolerance`, failing instead of splitting more
+/// than `recursion_limit` levels deep
+#[wasm_bindgen]
+pub fn apply_douglas_peucker_recursive(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+    recursion_limit: usize,
+) -> Result<Curve, String> {
+    fn retain(
+        x: &[f64],
+        y: &[f64],
+        (start, end): (usize, usize),
+        tolerance: f64,
+        depth: usize,
+        retained: &mut Vec<usize>,
+    ) -> Result<(), String> {
+        let Some(index) = farthest_point(x, y, start, end, tolerance) else {
+            return Ok(());
+        };
+
+        if depth == 0 {
+            return Err("The recursion limit was reached.".to_string());
+        }
+
+        retain(x, y, (start, index), tolerance, depth - 1, retained)?;
+        retained.push(index);
+        retain(x, y, (index, end), tolerance, depth - 1, retained)
+    }
+
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let last = timestamps.len() - 1;
+    let mut retained = vec![0];
+    retain(x, y, (0, last), tolerance, recursion_limit, &mut retained)?;
+    retained.push(last);
+
+    Ok(Curve {
+        timestamps: timestamps.to_vec(),
+        x: x.to_vec(),
+        y: y.to_vec(),
+    }
+    .select(&retained))
+}
+
+/// `decimate_by_tolerance` that always keeps the points at `keyframe_timestamps`, by decimating
+/// the pieces between keyframes separately
+#[wasm_bindgen]
+pub fn simplify_to_keyframes(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    keyframe_timestamps: &[f64],
+    tolerance: f64,
+) -> Result<Curve, String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let mut boundaries = vec![0, timestamps.len() - 1];
+
+    for &keyframe in keyframe_timestamps {
+        let index = timestamps
+            .iter()
+            .position(|&t| t == keyframe)
+            .ok_or_else(|| format!("The keyframe timestamp {} is not in the curve.", keyframe))?;
+        boundaries.push(index);
+    }
+
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut curve = Curve {
+        timestamps: vec![timestamps[0]],
+        x: vec![x[0]],
+        y: vec![y[0]],
+    };
+
+    for pair in boundaries.windows(2) {
+        let range = pair[0]..pair[1] + 1;
+        let piece = decimate_by_tolerance(
+            &timestamps[range.clone()],
+            &x[range.clone()],
+            &y[range],
+            tolerance,
+        )?;
+
+        // the first point of every piece is the last point of the one before
+        curve.timestamps.extend_from_slice(&piece.timestamps[1..]);
+        curve.x.extend_from_slice(&piece.x[1..]);
+        curve.y.extend_from_slice(&piece.y[1..]);
+    }
+
+    Ok(curve)
+}
+
+/// error of every original point, its perpendicular distance from the decimated segment that
+/// spans its timestamp
+#[wasm_bindgen]
+pub fn quality_report(
+    original_timestamps: &[f64],
+    original_x: &[f64],
+    original_y: &[f64],
+    decimated: &Curve,
+) -> Result<DecimationReport, String> {
+    check_lengths(original_timestamps, original_x, original_y)?;
+
+    if decimated.len() < 2 {
+        return Err("The decimated curve must contain at least two points.".to_string());
+    }
+
+    let (start, end) = (
+        decimated.timestamps[0],
+        decimated.timestamps[decimated.len() - 1],
+    );
+
+    if original_timestamps.is_empty() {
+        return Err("The original curve must contain at least one point.".to_string());
+    }
+
+    let mut errors = Vec::with_capacity(original_timestamps.len());
+
+    for i in 0..original_timestamps.len() {
+        let t = original_timestamps[i];
+
+        if !(start..=end).contains(&t) {
+            return Err(format!(
+                "The timestamp {} is outside the decimated curve.",
+                t
+            ));
+        }
+
+        let segment = decimated
+            .timestamps
+            .partition_point(|&retained| retained < t)
+            .clamp(1, decimated.len() - 1);
+
+        errors.push(perpendicular_distance(
+            original_x[i],
+            original_y[i],
+            decimated.x[segment - 1],
+            decimated.y[segment - 1],
+            decimated.x[segment],
+            decimated.y[segment],
+        ));
+    }
+
+    let n = errors.len() as f64;
+    let mean_error = errors.iter().sum::<f64>() / n;
+    let variance = errors.iter().map(|e| (e - mean_error).powi(2)).sum::<f64>() / n;
+    let (max_at, max_error) =
+        errors
+            .iter()
+            .copied()
+            .enumerate()
+            .fold(
+                (0, f64::NEG_INFINITY),
+                |max, (i, e)| if e > max.1 { (i, e) } else { max },
+            );
+
+    Ok(DecimationReport {
+        max_error,
+        mean_error,
+        error_stddev: variance.sqrt(),
+        max_error_at_timestamp: original_timestamps[max_at],
+    })
+}
+
+/// `decimate_by_tolerance` that removes the points from `curve` instead of building a new one
+#[wasm_bindgen]
+pub fn decimate_by_tolerance_in_place(curve: &mut Curve, tolerance: f64) -> Result<(), String> {
+    let retained = douglas_peucker_mask(&curve.timestamps, &curve.x, &curve.y, tolerance)?;
+
+    for values in [&mut curve.timestamps, &mut curve.x, &mut curve.y] {
+        let mut keep = retained.iter();
+        values.retain(|_| *keep.next().unwrap());
+    }
+
+    Ok(())
+}
+
+/// runs `decimate_by_tolerance` but returns, for every input point, whether it would be retained,
+/// so the same selection can be applied to other channels indexed like the input
+pub fn douglas_peucker_mask(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+) -> Result<Vec<bool>, String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let last = timestamps.len() - 1;
+    let mut mask = vec![false; last + 1];
+    mask[0] = true;
+    mask[last] = true;
+    let mut stack = vec![(0, last)];
+
+    while let Some((start, end)) = stack.pop() {
+        if let Some(index) = farthest_point(x, y, start, end, tolerance) {
+            mask[index] = true;
+            stack.push((start, index));
+            stack.push((index, end));
+        }
+    }
+
+    Ok(mask)
+}
+
+/// `douglas_peucker_mask` with a byte per point, one for retained points and zero for the rest
+#[wasm_bindgen(js_name = douglas_peucker_mask)]
+pub fn douglas_peucker_mask_js(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+) -> Result<Uint8Array, String> {
+    let mask: Vec<u8> = douglas_peucker_mask(timestamps, x, y, tolerance)?
+        .into_iter()
+        .map(u8::from)
+        .collect();
+
+    Ok(Uint8Array::from(&mask[..]))
+}
+
+/// the points of the original arrays whose entry in `mask` is set, the inverse of
+/// `douglas_peucker_mask` for masks that have been edited by hand
+pub fn combine_with_mask(
+    original_timestamps: &[f64],
+    original_x: &[f64],
+    original_y: &[f64],
+    mask: &[bool],
+) -> Result<Curve, String> {
+    check_lengths(original_timestamps, original_x, original_y)?;
+
+    if mask.len() != original_timestamps.len() {
+        return Err("The mask must have an entry for every point.".to_string());
+    }
+
+    if !(mask.first() == Some(&true) && mask.last() == Some(&true)) {
+        return Err("The mask must retain the first and last points.".to_string());
+    }
+
+    let retained = |values: &[f64]| {
+        values
+            .iter()
+            .zip(mask)
+            .filter(|(_, &keep)| keep)
+            .map(|(&value, _)| value)
+            .collect()
+    };
+
+    Ok(Curve {
+        timestamps: retained(original_timestamps),
+        x: retained(original_x),
+        y: retained(original_y),
+    })
+}
+
+/// `combine_with_mask` with a byte per point, as returned by `douglas_peucker_mask`, where any
+/// non-zero byte retains the point
+#[wasm_bindgen(js_name = combine_with_mask)]
+pub fn combine_with_mask_js(
+    original_timestamps: &[f64],
+    original_x: &[f64],
+    original_y: &[f64],
+    mask: &[u8],
+) -> Result<Curve, String> {
+    let mask: Vec<bool> = mask.iter().map(|&byte| byte != 0).collect();
+
+    combine_with_mask(original_timestamps, original_x, original_y, &mask)
+}
+
+/// decimates the x and y of `curve` like `decimate_by_tolerance` and keeps the values of every
+/// additional channel at the retained points
+#[wasm_bindgen]
+pub fn decimate_by_tolerance_multichannel(
+    curve: &MultiChannelCurve,
+    tolerance: f64,
+) -> Result<MultiChannelCurve, String> {
+    let Curve { timestamps, x, y } = &curve.curve;
+    let mask = douglas_peucker_mask(timestamps, x, y, tolerance)?;
+    let channels = curve
+        .channels
+        .iter()
+        .map(|(name, values)| {
+            let retained = values
+                .iter()
+                .zip(&mask)
+                .filter(|(_, &keep)| keep)
+                .map(|(&value, _)| value)
+                .collect();
+            (name.clone(), retained)
+        })
+        .collect();
+
+    Ok(MultiChannelCurve {
+        curve: combine_with_mask(timestamps, x, y, &mask)?,
+        channels,
+    })
+}
+
+/// simplifies the curve with the Lang algorithm, which looks at most `window` points ahead of the
+/// last retained point and keeps the farthest point for which every point in between is within
+/// `tolerance` of the line to it
+#[wasm_bindgen]
+pub fn lang_by_tolerance(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+    window: usize,
+) -> Result<Curve, String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    if window < 1 {
+        return Err("The window must contain at least one point.".to_string());
+    }
+
+    let last = timestamps.len() - 1;
+    let mut curve = Curve {
+        timestamps: vec![timestamps[0]],
+        x: vec![x[0]],
+        y: vec![y[0]],
+    };
+    let mut anchor = 0;
+
+    while anchor < last {
+        let mut end = (anchor + window).min(last);
+
+        while end > anchor + 1
+            && ((anchor + 1)..end).any(|i| {
+                perpendicular_distance(x[i], y[i], x[anchor], y[anchor], x[end], y[end]) > tolerance
+            })
+        {
+            end -= 1;
+        }
+
+        curve.timestamps.push(timestamps[end]);
+        curve.x.push(x[end]);
+        curve.y.push(y[end]);
+        anchor = end;
+    }
+
+    Ok(curve)
+}
+
+#[wasm_bindgen]
+pub fn lang_to_count(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+    window: usize,
+) -> Result<Curve, String> {
+    check_count(timestamps, x, y, count)?;
+
+    let mut max_distance = distance(x[0], y[0], x[1], y[1]);
+
+    for i in 1..(x.len() - 1) {
+        max_distance = max_distance.max(distance(x[i], y[i], x[i + 1], y[i + 1]));
+    }
+
+    search_tolerance(
+        count..=count,
+        max_distance,
+        &SearchOptions::default(),
+        timestamps.len(),
+        |tolerance| lang_by_tolerance(timestamps, x, y, tolerance, window),
+    )
+    .map(|(curve, _)| curve)
+}
+
+/// simplifies the curve with the Visvalingam-Whyatt algorithm, which repeatedly removes the point
+/// forming the smallest triangle with its current neighbours until `count` points remain
+#[wasm_bindgen]
+pub fn visvalingam_whyatt_to_count(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+) -> Result<Curve, String> {
+    check_count(timestamps, x, y, count)?;
+
+    let last = timestamps.len() - 1;
+    let triangle_area = |a: usize, b: usize, c: usize| {
+        ((x[b] - x[a]) * (y[c] - y[a]) - (x[c] - x[a]) * (y[b] - y[a])).abs() / 2.0
+    };
+    let mut previous: Vec<usize> = (0..=last).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=last + 1).collect();
+    let mut areas: Vec<f64> = (0..=last)
+        .map(|i| {
+            if i == 0 || i == last {
+                f64::INFINITY
+            } else {
+                triangle_area(i - 1, i, i + 1)
+            }
+        })
+        .collect();
+    let mut removed = vec![false; last + 1];
+    let mut heap: std::collections::BinaryHeap<EffectiveArea> = (1..last)
+        .map(|index| EffectiveArea {
+            area: areas[index],
+            index,
+        })
+        .collect();
+
+    let mut remaining = last + 1;
+
+    while remaining > count {
+        let Some(EffectiveArea { area, index }) = heap.pop() else {
+            break;
+        };
+
+        // entries left behind when a neighbour's area was recomputed
+        if removed[index] || area != areas[index] {
+            continue;
+        }
+
+        removed[index] = true;
+        remaining -= 1;
+        let (before, after) = (previous[index], next[index]);
+        next[before] = after;
+        previous[after] = before;
+
+        for i in [before, after] {
+            if i > 0 && i < last {
+                // never smaller than the area just removed, so removal order follows importance
+                areas[i] = triangle_area(previous[i], i, next[i]).max(area);
+                heap.push(EffectiveArea {
+                    area: areas[i],
+                    index: i,
+                });
+            }
+        }
+    }
+
+    let kept: Vec<usize> = (0..=last).filter(|&i| !removed[i]).collect();
+
+    Ok(Curve {
+        timestamps: kept.iter().map(|&i| timestamps[i]).collect(),
+        x: kept.iter().map(|&i| x[i]).collect(),
+        y: kept.iter().map(|&i| y[i]).collect(),
+    })
+}
+
+/// a point queued for removal by `visvalingam_whyatt_to_count`, ordered so that the smallest area
+/// is at the top of a `BinaryHeap`
+#[derive(PartialEq)]
+struct EffectiveArea {
+    area: f64,
+    index: usize,
+}
+
+impl Eq for EffectiveArea {}
+
+impl PartialOrd for EffectiveArea {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EffectiveArea {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+/// decimates the curve to `count` points with whichever algorithm suits it, returning the curve
+/// along with the name of the algorithm used
+///
+/// The deviation of every interior point from the chord between its neighbours is measured
+/// relative to the mean segment length. Noisy curves, where that deviation varies a lot, go to
+/// Visvalingam-Whyatt, which removes small wiggles first. Smooth curves that turn sharply on
+/// average go to Lang, which follows their corners locally, and everything else goes to
+/// Ramer-Douglas-Peucker.
+pub fn decimate_adaptive(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    count: usize,
+) -> Result<(Curve, &'static str), String> {
+    check_count(timestamps, x, y, count)?;
+
+    let curve = Curve {
+        timestamps: timestamps.to_vec(),
+        x: x.to_vec(),
+        y: y.to_vec(),
+    };
+    let segments = timestamps.len() - 1;
+    let mean_length = (1..=segments)
+        .map(|i| distance(x[i - 1], y[i - 1], x[i], y[i]))
+        .sum::<f64>()
+        / segments as f64;
+    let (noise, smoothness) = if timestamps.len() < 3 || mean_length == 0.0 {
+        (0.0, 0.0)
+    } else {
+        let turning = (1..segments)
+            .map(|i| turn_angle((x[i - 1], y[i - 1]), (x[i], y[i]), (x[i + 1], y[i + 1])))
+            .sum::<f64>()
+            / (segments - 1) as f64;
+        (curve.estimate_noise_level() / mean_length, turning)
+    };
+
+    if noise > ADAPTIVE_NOISE_THRESHOLD {
+        visvalingam_whyatt_to_count(timestamps, x, y, count).map(|c| (c, "visvalingam-whyatt"))
+    } else if smoothness > ADAPTIVE_TURNING_THRESHOLD {
+        let window = (2 * timestamps.len() / count).max(2);
+        lang_to_count(timestamps, x, y, count, window).map(|c| (c, "lang"))
+    } else {
+        decimate_to_count(timestamps, x, y, count).map(|c| (c, "ramer-douglas-peucker"))
+    }
+}
+
+/// splits the curve at the farthest point like `decimate_by_tolerance`, but decimates both sides
+/// of large splits on separate rayon tasks, the result is identical to `decimate_by_tolerance`
+#[cfg(feature = "parallel")]
+pub fn decimate_by_tolerance_parallel(
+    timestamps: &[f64],
+    x: &[f64],
+    y: &[f64],
+    tolerance: f64,
+) -> Result<Curve, String> {
+    check_lengths(timestamps, x, y)?;
+
+    if timestamps.len() < 2 {
+        return Err("Curve must contain at least two points.".to_string());
+    }
+
+    let last = timestamps.len() - 1;
+    let mut indices = vec![0];
+    indices.extend(retained_indices_parallel(x, y, 0, last, tolerance));
+    indices.push(last);
+
+    Ok(Curve {
+        timestamps: indices.iter().map(|&i| timestamps[i]).collect(),
+        x: indices.iter().map(|&i| x[i]).collect(),
+        y: indices.iter().map(|&i| y[i]).collect(),
+    })
+}
+
+/// sorted indices of the points strictly between `start` and `end` that are retained
+#[cfg(feature = "parallel")]
+fn retained_indices_parallel(
+    x: &[f64],
+    y: &[f64],
+    start: usize,
+    end: usize,
+    tolerance: f64,
+) -> Vec<usize> {
+    // spawning tasks for small splits costs more than it saves
+    if end - start < PARALLEL_MIN_POINTS {
+        let mut retained = Vec::new();
+        let mut stack = vec![(start, end)];
+
+        while let Some((start, end)) = stack.pop() {
+            if let Some(i) = farthest_point(x, y, start, end, tolerance) {
+                retained.push(i);
+                stack.push((start, i));
+                stack.push((i, end));
+            }
+        }
+
+        retained.sort_unstable();
+        return retained;
+    }
+
+    match farthest_point(x, y, start, end, tolerance) {
+        Some(i) => {
+            let (mut left, right) = rayon::join(
+                || retained_indices_parallel(x, y, start, i, tolerance),
+                || retained_indices_parallel(x, y, i, end, tolerance),
+            );
+
+            // the split point is the end of the left side and the start of the right side, which
+            // are both excluded from the results, so it's added exactly once here
+            left.push(i);
+            left.extend(right);
+            left
+        }
+        None => Vec::new(),
+    }
+}
+
+/// the first point between `start` and `end` with the largest perpendicular distance to the line
+/// through them, if that distance is above the tolerance
+fn farthest_point(x: &[f64], y: &[f64], start: usize, end: usize, tolerance: f64) -> Option<usize> {
+    if end <= start + 1 {
+        return None;
+    }
+
+    let mut dmax = perpendicular_distance(
+        x[start + 1],
+        y[start + 1],
+        x[start],
+        y[start],
+        x[end],
+        y[end],
+    );
+    let mut dmax_is_at = start + 1;
+
+    for i in (start + 2)..end {
+        let d = perpendicular_distance(x[i], y[i], x[start], y[start], x[end], y[end]);
+
+        if d > dmax {
+            dmax = d;
+            dmax_is_at = i;
+        }
+    }
+
+    (dmax > tolerance).then_some(dmax_is_at)
+}
+
+fn check_count(timestamps: &[f64], x: &[f64], y: &[f64], count: usize) -> Result<(), String> {
+    if count < 2 {
+        return Err("The count must be at least two.".to_string());
+    }
+
+    if timestamps.len() < count {
+        return Err("The curve does not have enough points.".to_string());
+    }
+
+    check_lengths(timestamps, x, y)
+}
+
+/// binary searches the tolerances between zero and `upper_limit`, or the limits set in `options`,
+/// for one at which `decimate` keeps a number of points within `counts`, returning the curve
+/// along with that tolerance
+///
+/// When the number of retained points doesn't change for `STALL_ITERATIONS` probes in a row, the
+/// next probe is moved away from the middle of the interval by a pseudo-random fraction of its
+/// width, which halves with every use. The generator is seeded with `seed` so the search stays
+/// deterministic for a given input.
+fn search_tolerance(
+    counts: std::ops::RangeInclusive<usize>,
+    upper_limit: f64,
+    options: &SearchOptions,
+    seed: usize,
+    decimate: impl Fn(f64) -> Result<Curve, String>,
+) -> Result<(Curve, f64), String> {
+    let mut lower_limit = options.initial_lower_limit.unwrap_or(0.0);
+    let mut upper_limit = options.initial_upper_limit.unwrap_or(upper_limit);
+    let mut middle: f64;
+    let mut curve = Curve {
+        timestamps: Vec::new(),
+        x: Vec::new(),
+        y: Vec::new(),
+    };
+    let mut random_state = seed as u64;
+    let mut jitter = 0.5;
+    let mut previous_len = None;
+    let mut stalled_iterations = 0;
+
+    // The loop may hit the limit if two values are somehow removed at the same(or almost the same) tolerance value.
+    for _ in 0..options.max_iterations {
+        middle = (upper_limit + lower_limit) / 2.0;
+
+        if stalled_iterations >= STALL_ITERATIONS {
+            middle += (next_random(&mut random_state) - 0.5) * jitter * (upper_limit - lower_limit);
+            jitter /= 2.0;
+            stalled_iterations = 0;
+        }
+
+        curve = decimate(middle)?;
+
+        if previous_len == Some(curve.len()) {
+            stalled_iterations += 1;
+        } else {
+            stalled_iterations = 0;
+        }
+        previous_len = Some(curve.len());
+
+        if counts.contains(&curve.len()) {
+            return Ok((curve, middle));
+        } else if curve.len() > *counts.end() {
+            lower_limit = middle;
+        } else {
+            upper_limit = middle;
+        }
+    }
+
+    Err(format!(
+        "Binary Search limit reached. Count: {} Middle: {}",
+        curve.timestamps.len(),
+        (upper_limit + lower_limit) / 2.0
+    ))
+}
+
+/// splitmix64, returns a value in `[0, 1)`
+fn next_random(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// just enough of a JSON reader for the curve format, to avoid pulling in serde
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn consume(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+
+        if self.bytes.get(self.position) == Some(&byte) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.consume(byte) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected '{}' at position {} of the JSON curve.",
+                byte as char, self.position
+            ))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+
+        if self.position == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Unexpected data at position {} of the JSON curve.",
+                self.position
+            ))
+        }
+    }
+
+    fn parse_key(&mut self) -> Result<&'a str, String> {
+        self.expect(b'"')?;
+
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|&byte| byte != b'"' && byte != b'\\')
+        {
+            self.position += 1;
+        }
+        let key = std::str::from_utf8(&self.bytes[start..self.position])
+            .map_err(|_| "Invalid UTF-8 in the JSON curve.".to_string())?;
+
+        self.expect(b'"')?;
+
+        Ok(key)
+    }
+
+    fn parse_value(&mut self) -> Result<f64, String> {
+        self.skip_whitespace();
+
+        if self.bytes[self.position..].starts_with(b"null") {
+            self.position += 4;
+            return Ok(f64::NAN);
+        }
+
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| b"+-.eE0123456789".contains(byte))
+        {
+            self.position += 1;
+        }
+
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| format!("Expected a number at position {} of the JSON curve.", start))
+    }
+
+    fn parse_array(&mut self) -> Result<Vec<f64>, String> {
+        let mut values = Vec::new();
+
+        self.expect(b'[')?;
+        if self.consume(b']') {
+            return Ok(values);
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+
+            if !self.consume(b',') {
+                break;
+            }
+        }
+        self.expect(b']')?;
+
+        Ok(values)
+    }
+}
+
+fn check_lengths(timestamps: &[f64], x: &[f64], y: &[f64]) -> Result<(), String> {
+    if (timestamps.len() != x.len()) || (timestamps.len() != y.len()) {
+        return Err(
+            "The number of values for timestamps, x-coordinates and y-coordinates don't match."
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+fn normalize_value(value: f64, min: f64, max: f64) -> f64 {
+    if max == min {
+        0.5
+    } else {
+        (value - min) / (max - min)
+    }
+}
+
+fn indices_to_array(indices: &[usize]) -> Uint32Array {
+    let indices: Vec<u32> = indices.iter().map(|&i| i as u32).collect();
+    Uint32Array::from(&indices[..])
+}
+
+fn point_to_array(point: Option<(f64, f64, f64)>) -> Float64Array {
+    match point {
+        Some((t, x, y)) => Float64Array::from(&[t, x, y][..]),
+        None => Float64Array::new_with_length(0),
+    }
+}
+
+/// C entry point for `Curve::as_ptr_triple`, writes the pointers to `timestamps`, `x` and `y` and
+/// returns the number of points
+///
+/// # Safety
+///
+/// `curve` must point to a live `Curve` and the output pointers must be valid for writes. The
+/// written pointers follow the rules of `Curve::as_ptr_triple`.
+#[no_mangle]
+pub unsafe extern "C" fn rdp_curve_data(
+    curve: *const Curve,
+    timestamps: *mut *const f64,
+    x: *mut *const f64,
+    y: *mut *const f64,
+) -> usize {
+    let (t_ptr, x_ptr, y_ptr, len) = (*curve).as_ptr_triple();
+
+    *timestamps = t_ptr;
+    *x = x_ptr;
+    *y = y_ptr;
+
+    len
+}
+
+/// decimates every curve in the array with the same tolerance, curves that can't be decimated become `null`
+#[wasm_bindgen]
+pub fn decimate_many(curves: Array, tolerance: f64) -> Array {
+    curves
+        .iter()
+        .map(|value| {
+            curve_from_js_value(&value)
+                .and_then(|curve| {
+                    decimate_by_tolerance(&curve.timestamps, &curve.x, &curve.y, tolerance)
+                        .map_err(JsValue::from)
+                })
+                .map_or(JsValue::NULL, JsValue::from)
+        })
+        .collect()
+}
+
+/// copies the data out of a JS `Curve` object through its getters, leaving the object usable
+fn curve_from_js_value(value: &JsValue) -> Result<Curve, JsValue> {
+    let channel = |getter: &str| -> Result<Vec<f64>, JsValue> {
+        let getter: Function = Reflect::get(value, &JsValue::from_str(getter))?.dyn_into()?;
+        Ok(getter.call0(value)?.dyn_into::<Float64Array>()?.to_vec())
+    };
+
+    Curve::from_vectors(
+        channel("get_timestamps")?,
+        channel("get_x")?,
+        channel("get_y")?,
+    )
+    .map_err(JsValue::from)
+}
+
+/// finite difference derivative of `values` with respect to `timestamps`
+fn derivative(timestamps: &[f64], values: &[f64]) -> Vec<f64> {
+    let n = values.len();
+
+    if n < 2 {
+        return vec![0.0; n];
+    }
+
+    (0..n)
+        .map(|i| {
+            let previous = i.saturating_sub(1);
+            let next = (i + 1).min(n - 1);
+
+            (values[next] - values[previous]) / (timestamps[next] - timestamps[previous])
+        })
+        .collect()
+}
+
+/// perpendicular distance between a point and a line defined by two points
+/// https://math.stackexchange.com/a/2757330
+fn perpendicular_distance(x: f64, y: f64, x1: f64, y1: f64, xn: f64, yn: f64) -> f64 {
+    let numerator = ((xn - x1) * (y - y1) - (yn - y1) * (x - x1)).abs();
+    let denominator = ((xn - x1).powi(2) + (yn - y1).powi(2)).sqrt();
+
+    // there is no line through two coincident points
+    if denominator < f64::EPSILON {
+        return distance(x, y, x1, y1);
+    }
+
+    numerator / denominator
+}
+
+/// distance between a point and the closest point of the segment between two points
+fn segment_distance(x: f64, y: f64, x1: f64, y1: f64, xn: f64, yn: f64) -> f64 {
+    let (dx, dy) = (xn - x1, yn - y1);
+    let length_squared = dx * dx + dy * dy;
+
+    if length_squared == 0.0 {
+        return distance(x, y, x1, y1);
+    }
+
+    let t = (((x - x1) * dx + (y - y1) * dy) / length_squared).clamp(0.0, 1.0);
+
+    distance(x, y, x1 + t * dx, y1 + t * dy)
+}
+
+/// distance between a point and a line through two points, all given as `(t, x, y)`
+fn perpendicular_distance_3d(p: (f64, f64, f64), p1: (f64, f64, f64), pn: (f64, f64, f64)) -> f64 {
+    let u = (pn.0 - p1.0, pn.1 - p1.1, pn.2 - p1.2);
+    let v = (p.0 - p1.0, p.1 - p1.1, p.2 - p1.2);
+    let cross = (
+        u.1 * v.2 - u.2 * v.1,
+        u.2 * v.0 - u.0 * v.2,
+        u.0 * v.1 - u.1 * v.0,
+    );
+
+    (cross.0.powi(2) + cross.1.powi(2) + cross.2.powi(2)).sqrt()
+        / (u.0.powi(2) + u.1.powi(2) + u.2.powi(2)).sqrt()
+}
+
+/// the point where segments `p1`-`p2` and `q1`-`q2` meet, or where their overlap starts if they
+/// are collinear
+fn segment_intersection(
+    p1: (f64, f64),
+    p2: (f64, f64),
+    q1: (f64, f64),
+    q2: (f64, f64),
+) -> Option<(f64, f64)> {
+    let r = (p2.0 - p1.0, p2.1 - p1.1);
+    let s = (q2.0 - q1.0, q2.1 - q1.1);
+    let qp = (q1.0 - p1.0, q1.1 - p1.1);
+    let cross = |a: (f64, f64), b: (f64, f64)| a.0 * b.1 - a.1 * b.0;
+
+    let denominator = cross(r, s);
+
+    if denominator == 0.0 {
+        let length_squared = r.0 * r.0 + r.1 * r.1;
+
+        if cross(qp, r) != 0.0 || length_squared == 0.0 {
+            return None;
+        }
+
+        let t0 = (qp.0 * r.0 + qp.1 * r.1) / length_squared;
+        let t1 = t0 + (s.0 * r.0 + s.1 * r.1) / length_squared;
+        let start = t0.min(t1).max(0.0);
+
+        if start > t0.max(t1).min(1.0) {
+            return None;
+        }
+
+        return Some((p1.0 + r.0 * start, p1.1 + r.1 * start));
+    }
+
+    let t = cross(qp, s) / denominator;
+    let u = cross(qp, r) / denominator;
+
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some((p1.0 + r.0 * t, p1.1 + r.1 * t))
+    } else {
+        None
+    }
+}
+
+/// like `turn_angle`, but positive for left turns and negative for right turns, a full reversal
+/// counts as a left turn of pi
+fn signed_turn_angle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    let incoming = (b.0 - a.0, b.1 - a.1);
+    let outgoing = (c.0 - b.0, c.1 - b.1);
+    let cross = incoming.0 * outgoing.1 - incoming.1 * outgoing.0;
+    let dot = incoming.0 * outgoing.0 + incoming.1 * outgoing.1;
+
+    if cross == 0.0 && dot < 0.0 {
+        // atan2 would pick the sign from the sign of the zero
+        std::f64::consts::PI
+    } else {
+        cross.atan2(dot)
+    }
+}
+
+/// change of direction at `b` when travelling from `a` to `c`, in radians; zero if either
+/// segment has no length
+fn turn_angle(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    let incoming = (b.0 - a.0, b.1 - a.1);
+    let outgoing = (c.0 - b.0, c.1 - b.1);
+    let cross = incoming.0 * outgoing.1 - incoming.1 * outgoing.0;
+    let dot = incoming.0 * outgoing.0 + incoming.1 * outgoing.1;
+
+    cross.abs().atan2(dot)
+}
+
+/// direction of `(dx, dy)` in degrees clockwise from the positive y axis, in `[0, 360)`
+fn bearing(dx: f64, dy: f64) -> f64 {
+    let degrees = dx.atan2(dy).to_degrees();
+
+    if degrees < 0.0 {
+        degrees + 360.0
+    } else {
+        degrees
+    }
+}
+
+fn is_monotone(values: &[f64]) -> bool {
+    values.windows(2).all(|pair| pair[0] <= pair[1])
+        || values.windows(2).all(|pair| pair[0] >= pair[1])
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// standard base64 with padding
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(BASE64_ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+fn base64_decode(encoded: &str) -> Result<Vec<u8>, String> {
+    let encoded = encoded.trim_end_matches('=').as_bytes();
+
+    if encoded.len() % 4 == 1 {
+        return Err("The base64 string has an invalid length.".to_string());
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+
+    for chunk in encoded.chunks(4) {
+        let mut group = 0u32;
+
+        for (i, &character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|&c| c == character)
+                .ok_or_else(|| format!("'{}' is not a base64 character.", character as char))?;
+            group |= (value as u32) << (18 - 6 * i);
+        }
+
+        for i in 0..(chunk.len() - 1) {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// calls `plot(x, y, coverage)` for the pixels of an anti-aliased line with Xiaolin Wu's algorithm
+fn draw_wu_line(
+    mut x0: f64,
+    mut y0: f64,
+    mut x1: f64,
+    mut y1: f64,
+    plot: &mut impl FnMut(i64, i64, f64),
+) {
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+
+    let mut plot = |x: i64, y: i64, coverage: f64| {
+        if steep {
+            plot(y, x, coverage)
+        } else {
+            plot(x, y, coverage)
+        }
+    };
+    let dx = x1 - x0;
+    let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+    // the endpoints are weighted by how much of their pixel the line covers horizontally
+    let mut endpoint = |x: f64, y: f64, gap: f64| {
+        let x_end = x.round();
+        let y_end = y + gradient * (x_end - x);
+        let row = y_end.floor();
+        plot(x_end as i64, row as i64, (1.0 - (y_end - row)) * gap);
+        plot(x_end as i64, row as i64 + 1, (y_end - row) * gap);
+        (x_end as i64, y_end)
+    };
+    let (start, y_start) = endpoint(x0, y0, 1.0 - (x0 + 0.5).fract());
+    let (end, _) = endpoint(x1, y1, (x1 + 0.5).fract());
+    let mut y = y_start + gradient;
+
+    for x in (start + 1)..end {
+        let row = y.floor();
+        plot(x, row as i64, 1.0 - (y - row));
+        plot(x, row as i64 + 1, y - row);
+        y += gradient;
+    }
+}
+
+/// appends `value` as a protobuf base 128 varint
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+/// reads a protobuf base 128 varint starting at `position`, moving `position` past it
+fn read_varint(bytes: &[u8], position: &mut usize) -> Result<u64, String> {
+    let mut value = 0;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*position)
+            .ok_or("The protobuf data ends in the middle of a varint.")?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+
+    Err("A protobuf varint is longer than ten bytes.".to_string())
+}
+
+fn lerp(a: f64, b: f64, fraction: f64) -> f64 {
+    a + (b - a) * fraction
+}
+
+fn distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perpendicular_distance_on_the_line() {
+        let x = 2.0;
+        let y = 2.0;
+        let x1 = 0.0;
+        let y1 = 0.0;
+        let xn = 4.0;
+        let yn = 4.0;
+
+        let result = perpendicular_distance(x, y, x1, y1, xn, yn);
+        assert!(
+            (result - 0.0).abs() < 1e-10,
+            "Expected distance to be 0, got {}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_perpendicular_distance_off_the_line() {
+        let x = 5.0;
+        let y = 11.0;
+        let x1 = 0.0;
+        let y1 = 0.0;
+        let xn = 20.0;
+        let yn = 20.0;
+
+        let result = perpendicular_distance(x, y, x1, y1, xn, yn);
+        assert!(
+            (result - (3.0 * (2.0_f64).sqrt())).abs() < 1e-10,
+            "Expected distance to be approximately {}, got {}",
+            3.0 / (2.0_f64).sqrt(),
+            result
+        );
+    }
+
+    fn assert_curve(actual: &Curve, expected: &Curve) {
+        assert_eq!(actual.timestamps, expected.timestamps);
+        assert_eq!(actual.x, expected.x);
+        assert_eq!(actual.y, expected.y);
+    }
+
+    #[test]
+    fn all_points_pruned() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
+        let tolerance = 100.0;
+
+        let result = decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap();
+
+        let expected_curve = Curve {
+            timestamps: vec![0.0, 4.0], // Only the start and end points are retained
+            x: vec![0.0, 4.0],
+            y: vec![0.0, 2.0],
+        };
+
+        assert_curve(&result, &expected_curve);
+    }
+
+    #[test]
+    fn no_points_pruned() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.9, 4.0, 5.0, 4.0];
+        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
+        let tolerance = 0.0;
+
+        let result = decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap();
+
+        let expected_curve = Curve { timestamps, x, y };
+
+        assert_curve(&result, &expected_curve);
+    }
+
+    #[test]
+    fn specific_count() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 3.5, 4.0];
+        let x = vec![0.0, 2.0, 67.2, 5.1, 6.0, 6.4];
+        let y = vec![0.0, 0.7, 1.0, 1.4, 2.2, 3.0];
+
+        let result = decimate_to_count(&timestamps, &x, &y, 3).unwrap();
+
+        let expected_curve = Curve {
+            timestamps: vec![0.0, 2.0, 4.0],
+            x: vec![0.0, 67.2, 6.4],
+            y: vec![0.0, 1.0, 3.0],
+        };
+
+        assert_curve(&result, &expected_curve);
+    }
+
+    #[test]
+    fn limit_reached() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0];
+        let x = vec![0.0, 0.0, 1.0, 4.0];
+        let y = vec![0.0, 1.0, 0.0, 4.0];
+
+        let result = decimate_to_count(&timestamps, &x, &y, 3);
+
+        // Both interior points are exactly as far from the first chord, and keeping either of them
+        // puts the other one above the tolerance, so no tolerance keeps exactly three points and
+        // jittering the search can't help.
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            "Binary Search limit reached. Count: 2 Middle: 0.7071067811865475".to_string()
+        );
+    }
+
+    #[test]
+    fn reverse_twice_is_identity() {
+        let original = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![3.0, 4.0, 5.0],
+            y: vec![6.0, 7.0, 8.0],
+        };
+
+        let reversed = original.reversed();
+        assert_eq!(reversed.timestamps, vec![2.0, 1.0, 0.0]);
+        assert_eq!(reversed.x, vec![5.0, 4.0, 3.0]);
+        assert_eq!(reversed.y, vec![8.0, 7.0, 6.0]);
+
+        let mut curve = reversed;
+        curve.reverse();
+        assert_curve(&curve, &original);
+    }
+
+    fn random_tolerance_curve() -> Curve {
+        let timestamps: Vec<f64> = vec![
+            0, 8, 16, 24, 33, 41, 48, 57, 64, 72, 80, 89, 97, 105, 112, 120, 128, 137, 145, 153,
+            161, 168, 176, 184, 193, 201, 208, 217, 224, 232, 240, 250, 256, 264, 272, 280, 288,
+            297, 305, 312, 320, 328, 336, 344, 353, 360, 368, 376, 384, 392, 401, 409, 416, 424,
+            433, 440, 448, 457, 465, 472, 480, 488, 496, 505, 513, 521, 528, 536, 544, 552, 560,
+            569, 576, 584, 592, 600, 648,
+        ]
+        .iter()
+        .map(|&x| x as f64)
+        .collect();
+        let x: Vec<f64> = vec![
+            77, 86, 100, 115, 143, 173, 209, 255, 304, 358, 412, 461, 499, 527, 552, 569, 584, 599,
+            609, 621, 636, 648, 660, 674, 684, 699, 714, 728, 741, 756, 768, 781, 791, 797, 806,
+            814, 821, 826, 832, 837, 844, 849, 856, 860, 864, 869, 876, 880, 886, 891, 895, 896,
+            899, 901, 903, 904, 905, 907, 908, 908, 909, 912, 912, 914, 914, 915, 916, 916, 916,
+            917, 917, 917, 919, 919, 919, 919, 919,
+        ]
+        .iter()
+        .map(|&x| x as f64)
+        .collect();
+        let y: Vec<f64> = vec![
+            54, 62, 74, 88, 111, 134, 162, 192, 229, 264, 304, 336, 364, 386, 404, 420, 432, 444,
+            455, 467, 484, 499, 513, 530, 543, 560, 577, 592, 608, 623, 634, 645, 654, 661, 669,
+            674, 680, 688, 693, 698, 703, 708, 715, 718, 724, 728, 733, 738, 741, 745, 748, 750,
+            752, 756, 757, 760, 762, 765, 767, 768, 772, 776, 780, 784, 786, 788, 791, 792, 795,
+            796, 798, 800, 801, 802, 803, 804, 805,
+        ]
+        .iter()
+        .map(|&x| x as f64)
+        .collect();
+
+        Curve { timestamps, x, y }
+    }
+
+    #[test]
+    fn random_tolerance() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+        let count = 13;
+
+        let result = decimate_to_count(&timestamps, &x, &y, count).unwrap();
+
+        let expected_curve = Curve {
+            timestamps: vec![0, 33, 48, 89, 112, 137, 224, 288, 297, 353, 416, 488, 648]
+                .iter()
+                .map(|&x| x as f64)
+                .collect(),
+            x: vec![
+                77, 143, 209, 461, 552, 599, 741, 821, 826, 864, 899, 912, 919,
+            ]
+            .iter()
+            .map(|&x| x as f64)
+            .collect(),
+            y: vec![
+                54, 111, 162, 336, 404, 444, 608, 680, 688, 724, 752, 776, 805,
+            ]
+            .iter()
+            .map(|&x| x as f64)
+            .collect(),
+        };
+
+        assert_curve(&result, &expected_curve);
+    }
+
+    #[test]
+    fn get_point_out_of_bounds() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![3.0, 4.0, 5.0],
+            y: vec![6.0, 7.0, 8.0],
+        };
+
+        assert_eq!(curve.get_point(1), Some((1.0, 4.0, 7.0)));
+        assert_eq!(curve.get_point(3), None);
+        assert_eq!(curve.get_first(), Some((0.0, 3.0, 6.0)));
+        assert_eq!(curve.get_last(), Some((2.0, 5.0, 8.0)));
+
+        let empty = Curve {
+            timestamps: Vec::new(),
+            x: Vec::new(),
+            y: Vec::new(),
+        };
+
+        assert_eq!(empty.get_first(), None);
+        assert_eq!(empty.get_last(), None);
+    }
+
+    #[test]
+    fn quantize_negative_coordinates() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![-0.4, -0.6, 1.4, 2.5],
+            y: vec![0.26, -0.74, 1.0, -1.1],
+        };
+
+        let result = curve.quantize(1.0, 0.5).unwrap();
+
+        assert_eq!(result.timestamps, curve.timestamps);
+        assert_eq!(result.x, vec![0.0, -1.0, 1.0, 3.0]);
+        assert_eq!(result.y, vec![0.5, -0.5, 1.0, -1.0]);
+        assert!(curve.quantize(0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn hausdorff_distance() {
+        let a = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 2.0],
+            y: vec![0.0, 0.0, 0.0],
+        };
+        let b = Curve {
+            timestamps: vec![0.0, 2.0],
+            x: vec![0.0, 2.0],
+            y: vec![0.0, 3.0],
+        };
+
+        assert_eq!(a.hausdorff_distance(&a), 0.0);
+        assert_eq!(a.directed_hausdorff_distance(&b), 2.0);
+        assert_eq!(b.directed_hausdorff_distance(&a), 3.0);
+        assert_eq!(a.hausdorff_distance(&b), 3.0);
+    }
+
+    #[test]
+    fn too_few_points() {
+        let result = decimate_by_tolerance(&[0.0], &[0.0], &[0.0], 1.0);
+        assert_eq!(
+            result.unwrap_err(),
+            "Curve must contain at least two points.".to_string()
+        );
+
+        let result = decimate_by_tolerance(&[], &[], &[], 1.0);
+        assert!(result.is_err());
+
+        let result = decimate_to_count(&[0.0, 1.0, 2.0], &[0.0, 1.0, 0.0], &[0.0, 1.0, 0.0], 1);
+        assert_eq!(
+            result.unwrap_err(),
+            "The count must be at least two.".to_string()
+        );
+    }
+
+    #[test]
+    fn zip_with_subtraction() {
+        let a = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![5.0, 6.0, 7.0],
+            y: vec![1.0, 1.0, 1.0],
+        };
+        let b = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![1.0, 2.0, 3.0],
+            y: vec![0.5, 1.5, 2.5],
+        };
+
+        let result = a.zip_with(&b, |x1, y1, x2, y2| (x1 - x2, y1 - y2)).unwrap();
+
+        let expected_curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![4.0, 4.0, 4.0],
+            y: vec![0.5, -0.5, -1.5],
+        };
+
+        assert_curve(&result, &expected_curve);
+
+        let c = Curve {
+            timestamps: vec![0.0, 1.0, 3.0],
+            x: vec![1.0, 2.0, 3.0],
+            y: vec![0.5, 1.5, 2.5],
+        };
+
+        assert!(a.zip_with(&c, |x1, y1, _, _| (x1, y1)).is_err());
+    }
+
+    #[test]
+    fn statistics() {
+        let curve = Curve {
+            timestamps: vec![1.0, 2.0, 3.0, 4.0],
+            x: vec![2.0, 4.0, 4.0, 6.0],
+            y: vec![-1.0, 1.0, -1.0, 1.0],
+        };
+
+        let expected = CurveStatistics {
+            x_mean: 4.0,
+            x_variance: 2.0,
+            x_min: 2.0,
+            x_max: 6.0,
+            y_mean: 0.0,
+            y_variance: 1.0,
+            y_min: -1.0,
+            y_max: 1.0,
+            t_min: 1.0,
+            t_max: 4.0,
+            point_count: 4,
+        };
+
+        assert_eq!(curve.statistics(), expected);
+
+        let single = Curve {
+            timestamps: vec![1.0],
+            x: vec![2.0],
+            y: vec![3.0],
+        };
+        let stats = single.statistics();
+
+        assert_eq!(stats.x_variance, 0.0);
+        assert_eq!(stats.y_variance, 0.0);
+        assert_eq!(stats.x_mean, 2.0);
+    }
+
+    #[test]
+    fn from_vectors_length_mismatch() {
+        let curve = Curve::from_vectors(vec![0.0, 1.0], vec![2.0, 3.0], vec![4.0, 5.0]).unwrap();
+        assert_eq!(curve.get_point(1), Some((1.0, 3.0, 5.0)));
+
+        let result = Curve::from_vectors(vec![0.0, 1.0], vec![2.0], vec![4.0, 5.0]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn normalize_corners() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![-10.0, 5.0, 10.0],
+            y: vec![100.0, 150.0, 300.0],
+        };
+
+        let result = curve.normalize();
+
+        let expected_curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 0.75, 1.0],
+            y: vec![0.0, 0.25, 1.0],
+        };
+
+        assert_curve(&result, &expected_curve);
+        assert_curve(&result.denormalize(-10.0, 10.0, 100.0, 300.0), &curve);
+
+        let vertical = Curve {
+            timestamps: vec![0.0, 1.0],
+            x: vec![3.0, 3.0],
+            y: vec![0.0, 1.0],
+        };
+
+        assert_eq!(vertical.normalize().x, vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn centroid_of_endpoints() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
+
+        let result = decimate_by_tolerance(&timestamps, &x, &y, 100.0).unwrap();
+
+        assert_eq!(result.centroid(), (2.0, 1.0));
+
+        let (x, y) = Curve::from_vectors(Vec::new(), Vec::new(), Vec::new())
+            .unwrap()
+            .centroid();
+        assert!(x.is_nan() && y.is_nan());
+    }
+
+    #[test]
+    fn derivatives_of_linear_curve() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 3.0, 4.0, 7.0],
+            x: vec![0.0, 2.0, 6.0, 8.0, 14.0],
+            y: vec![1.0, 0.5, -0.5, -1.0, -2.5],
+        };
+
+        let (dx_dt, dy_dt) = curve.compute_derivatives();
+
+        assert_eq!(dx_dt, vec![2.0; 5]);
+        assert_eq!(dy_dt, vec![-0.5; 5]);
+
+        let single = Curve {
+            timestamps: vec![1.0],
+            x: vec![2.0],
+            y: vec![3.0],
+        };
+
+        assert_eq!(single.compute_derivatives(), (vec![0.0], vec![0.0]));
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.5, 3.25],
+            x: vec![-1.0, f64::MAX, 0.1],
+            y: vec![f64::MIN_POSITIVE, 2.0, -7.75],
+        };
+
+        let bytes = curve.to_binary();
+        assert_eq!(bytes.len(), 8 + 3 * 24);
+        assert_curve(&Curve::from_binary(&bytes).unwrap(), &curve);
+
+        assert!(Curve::from_binary(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Curve::from_binary(b"nope").is_err());
+
+        // 0x2000_0001 * 24 wraps around to 24 in 32-bit arithmetic
+        let mut oversized = BINARY_MAGIC.to_vec();
+        oversized.extend_from_slice(&0x2000_0001u32.to_le_bytes());
+        oversized.extend_from_slice(&[0; 24]);
+        assert!(Curve::from_binary(&oversized).is_err());
+    }
+
+    #[test]
+    fn decimate_with_metrics() {
+        let timestamps = vec![0.0, 1.0, 2.0, 10.0];
+        let x = vec![0.0, 12.0, 2.5, 2.0];
+        let y = vec![0.0, 0.0, 0.5, 0.0];
+
+        // the second point is on the line through the endpoints, but beyond the end of the segment
+        let result = decimate_by_tolerance_with_metric(
+            &timestamps,
+            &x,
+            &y,
+            1.0,
+            DistanceMetric::PerpendicularProjection,
+        )
+        .unwrap();
+        assert_eq!(result.timestamps, vec![0.0, 10.0]);
+
+        let result =
+            decimate_by_tolerance_with_metric(&timestamps, &x, &y, 1.0, DistanceMetric::Euclidean)
+                .unwrap();
+        assert_eq!(result.timestamps, vec![0.0, 1.0, 10.0]);
+
+        // the middle point is close in space but far from the line in time
+        let timestamps = vec![0.0, 9.0, 10.0];
+        let x = vec![0.0, 0.5, 2.0];
+        let y = vec![0.0, 0.0, 0.0];
+
+        let result =
+            decimate_by_tolerance_with_metric(&timestamps, &x, &y, 1.0, DistanceMetric::Euclidean)
+                .unwrap();
+        assert_eq!(result.timestamps, vec![0.0, 10.0]);
+
+        let result = decimate_by_tolerance_with_metric(
+            &timestamps,
+            &x,
+            &y,
+            1.0,
+            DistanceMetric::TimeWeighted,
+        )
+        .unwrap();
+        assert_eq!(result.timestamps, vec![0.0, 9.0, 10.0]);
+    }
+
+    #[test]
+    fn window_decimate_random_tolerance() {
+        let curve = random_tolerance_curve();
+        let tolerance = 5.0;
+
+        let result = curve.window_decimate(30, tolerance).unwrap();
+
+        let mut expected_curve = decimate_by_tolerance(
+            &curve.timestamps[..30],
+            &curve.x[..30],
+            &curve.y[..30],
+            tolerance,
+        )
+        .unwrap();
+        for (start, end) in [(29, 59), (58, 77)] {
+            let window = decimate_by_tolerance(
+                &curve.timestamps[start..end],
+                &curve.x[start..end],
+                &curve.y[start..end],
+                tolerance,
+            )
+            .unwrap();
+            expected_curve
+                .timestamps
+                .extend_from_slice(&window.timestamps[1..]);
+            expected_curve.x.extend_from_slice(&window.x[1..]);
+            expected_curve.y.extend_from_slice(&window.y[1..]);
+        }
+
+        assert_curve(&result, &expected_curve);
+        assert!(result.timestamps.contains(&curve.timestamps[29]));
+        assert!(result.timestamps.contains(&curve.timestamps[58]));
+        assert!(result.timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn peak_detection_prominence() {
+        let mut curve = Curve::from_timestamp_fn(17, 0.0, 16.0, |t| {
+            (t, (t * std::f64::consts::FRAC_PI_4).sin())
+        });
+        // a small bump in the first trough
+        curve.y[6] = -0.6;
+
+        assert_eq!(curve.peak_detection(0.5), vec![2, 10]);
+        assert_eq!(curve.peak_detection(0.05), vec![2, 6, 10]);
+        assert_eq!(curve.peak_detection(5.0), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn triangular_wave_crossings() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            x: vec![0.0, 1.0, 0.0, 1.0, 0.0, 0.5, 1.0],
+            y: vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        };
+
+        assert_eq!(
+            curve.threshold_crossings(0.5, Channel::X),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(curve.rising_crossings(0.5, Channel::X), vec![0, 2, 4]);
+        assert_eq!(curve.falling_crossings(0.5, Channel::X), vec![1, 3]);
+        assert!(curve.threshold_crossings(0.5, Channel::Y).is_empty());
+    }
+
+    #[test]
+    fn hausdorff_distance_to_original_polyline() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = vec![0.0, 0.5, -0.25, 0.1, 0.0];
+
+        let result = decimate_by_tolerance(&timestamps, &x, &y, 1.0).unwrap();
+
+        assert_eq!(result.hausdorff_distance_to_polyline(&x, &y), Ok(0.5));
+        // point to point would measure the distance to the closest endpoint instead
+        assert_eq!(
+            result.hausdorff_distance(&Curve {
+                timestamps,
+                x: x.clone(),
+                y: y.clone()
+            }),
+            2.0_f64.hypot(0.25)
+        );
+        assert!(result.hausdorff_distance_to_polyline(&x, &y[1..]).is_err());
+    }
+
+    #[test]
+    fn insert_keeps_timestamps_sorted() {
+        let mut curve = Curve {
+            timestamps: vec![0.0, 2.0],
+            x: vec![0.0, 2.0],
+            y: vec![0.0, 2.0],
+        };
+
+        assert!(curve.insert(1, 1.0, 5.0, 6.0).is_ok());
+        assert!(curve.insert(3, 3.0, 7.0, 8.0).is_ok());
+        assert!(curve.insert(0, -1.0, 9.0, 9.0).is_ok());
+        assert_eq!(curve.timestamps, vec![-1.0, 0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(curve.x, vec![9.0, 0.0, 5.0, 2.0, 7.0]);
+
+        assert!(curve.insert(2, 0.0, 0.0, 0.0).is_err());
+        assert!(curve.insert(2, 1.5, 0.0, 0.0).is_err());
+        assert!(curve.insert(0, 4.0, 0.0, 0.0).is_err());
+        assert!(curve.insert(6, 4.0, 0.0, 0.0).is_err());
+        assert_eq!(curve.len(), 5);
+    }
+
+    #[test]
+    fn duplicate_timestamps_are_rejected() {
+        let timestamps = vec![0.0, 1.0, 1.0, 2.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0];
+        let y = vec![0.0, 5.0, -5.0, 0.0];
+
+        assert!(decimate_by_tolerance(&timestamps, &x, &y, 0.1).is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.5, 1e21],
+            x: vec![-1.0, 0.1, 1e-7],
+            y: vec![3.0, f64::MAX, -7.75],
+        };
+
+        let json = curve.to_json_string();
+        assert_curve(&Curve::from_json_str(&json).unwrap(), &curve);
+
+        let parsed =
+            Curve::from_json_str(" { \"y\" : [ 3 , null ] , \"x\": [1,2], \"t\":[0, 1e3] } ")
+                .unwrap();
+        assert_eq!(parsed.timestamps, vec![0.0, 1000.0]);
+        assert_eq!(parsed.x, vec![1.0, 2.0]);
+        assert!(parsed.y[1].is_nan());
+
+        let empty = Curve::from_json_str("{\"t\":[],\"x\":[],\"y\":[]}").unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn malformed_json() {
+        for json in [
+            "",
+            "{",
+            "{\"t\":[0],\"x\":[1]}",
+            "{\"t\":[0],\"x\":[1],\"y\":[2,3]}",
+            "{\"t\":[0],\"x\":[1],\"y\":[2],\"z\":[3]}",
+            "{\"t\":[0],\"x\":[1],\"y\":[2],\"t\":[3]}",
+            "{\"t\":[0,],\"x\":[1],\"y\":[2]}",
+            "{\"t\":[0],\"x\":[1],\"y\":[abc]}",
+            "{\"t\":[0],\"x\":[1],\"y\":[2]} trailing",
+        ] {
+            assert!(Curve::from_json_str(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn frechet_distance() {
+        let curve = random_tolerance_curve();
+        assert_eq!(curve.compute_frechet_distance(&curve), 0.0);
+
+        // the man walks along the x axis while the dog walks on a bumpy path above him
+        let man = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 1.0, 2.0, 3.0],
+            y: vec![0.0, 0.0, 0.0, 0.0],
+        };
+        let dog = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.5, 3.0],
+            y: vec![1.0, 2.0, 1.0],
+        };
+
+        assert_eq!(man.compute_frechet_distance(&dog), 0.5_f64.hypot(2.0));
+        assert_eq!(dog.compute_frechet_distance(&man), 0.5_f64.hypot(2.0));
+
+        // hausdorff ignores the order in which the points are visited
+        let reversed = man.reversed();
+        assert_eq!(man.hausdorff_distance(&reversed), 0.0);
+        assert_eq!(man.compute_frechet_distance(&reversed), 3.0);
+    }
+
+    #[test]
+    fn lang_compared_to_rdp() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+        let tolerance = 5.0;
+
+        let rdp = decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap();
+        let lang = lang_by_tolerance(&timestamps, &x, &y, tolerance, 10).unwrap();
+
+        assert_eq!(lang.get_first(), rdp.get_first());
+        assert_eq!(lang.get_last(), rdp.get_last());
+        // the short look ahead costs a few extra points on this curve
+        assert_eq!(rdp.len(), 7);
+        assert_eq!(lang.len(), 10);
+
+        // both stay within the tolerance of the original points
+        let rdp_error = rdp.hausdorff_distance_to_polyline(&x, &y).unwrap();
+        let lang_error = lang.hausdorff_distance_to_polyline(&x, &y).unwrap();
+        assert!(rdp_error <= tolerance, "{}", rdp_error);
+        assert!(lang_error <= tolerance, "{}", lang_error);
+
+        let lang = lang_to_count(&timestamps, &x, &y, 13, 10).unwrap();
+        assert_eq!(lang.len(), 13);
+
+        // a window of a single point can't skip anything
+        let lang = lang_by_tolerance(&timestamps, &x, &y, 1000.0, 1).unwrap();
+        assert_eq!(lang.timestamps, timestamps);
+    }
+
+    #[test]
+    fn pairs_and_triples_round_trip() {
+        let curve = random_tolerance_curve();
+
+        assert_curve(&Curve::from_triples(curve.to_triples()), &curve);
+
+        let pairs = curve.to_pairs();
+        assert_eq!(pairs[1], (86.0, 62.0));
+
+        let from_pairs = Curve::from_pairs(pairs.clone(), 10.0, 0.5);
+        assert_eq!(from_pairs.to_pairs(), pairs);
+        assert_eq!(from_pairs.timestamps[..3], [10.0, 10.5, 11.0]);
+    }
+
+    #[test]
+    fn from_timestamp_fn_samples_evenly() {
+        let curve = Curve::from_timestamp_fn(5, 1.0, 3.0, |t| (2.0 * t, t * t));
+
+        let expected_curve = Curve {
+            timestamps: vec![1.0, 1.5, 2.0, 2.5, 3.0],
+            x: vec![2.0, 3.0, 4.0, 5.0, 6.0],
+            y: vec![1.0, 2.25, 4.0, 6.25, 9.0],
+        };
+
+        assert_curve(&curve, &expected_curve);
+        assert_eq!(
+            Curve::from_timestamp_fn(1, 1.0, 3.0, |t| (t, t)).timestamps,
+            vec![1.0]
+        );
+        assert!(Curve::from_timestamp_fn(0, 1.0, 3.0, |t| (t, t)).is_empty());
+    }
+
+    #[test]
+    fn remove_inserted_outlier() {
+        let original = random_tolerance_curve();
+        let mut curve = original.clone();
+        curve.insert(41, 324.0, 846.0, 950.0).unwrap();
+
+        let result = curve.remove_outliers(2.0);
+
+        // only the spike is removed, its neighbours stay within the threshold
+        assert_curve(&result, &original);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_sequential() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+
+        for tolerance in [0.0, 1.0, 5.0, 100.0] {
+            assert_curve(
+                &decimate_by_tolerance_parallel(&timestamps, &x, &y, tolerance).unwrap(),
+                &decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap(),
+            );
+        }
+
+        let curve = Curve::from_timestamp_fn(50_000, 0.0, 500.0, |t| {
+            (t.cos() * t, t.sin() * t + (t * 37.0).sin())
+        });
+
+        assert_curve(
+            &decimate_by_tolerance_parallel(&curve.timestamps, &curve.x, &curve.y, 0.5).unwrap(),
+            &decimate_by_tolerance(&curve.timestamps, &curve.x, &curve.y, 0.5).unwrap(),
+        );
+    }
+
+    #[test]
+    fn decimation_is_symmetric_in_x_and_y() {
+        let curve = random_tolerance_curve();
+        let flipped = curve.flip_axes();
+
+        assert_eq!(flipped.x, curve.y);
+        assert_eq!(flipped.y, curve.x);
+
+        for tolerance in [0.5, 2.0, 10.0] {
+            let result = decimate_by_tolerance(&curve.timestamps, &curve.x, &curve.y, tolerance)
+                .unwrap()
+                .flip_axes();
+            let flipped_result =
+                decimate_by_tolerance(&flipped.timestamps, &flipped.x, &flipped.y, tolerance)
+                    .unwrap();
+
+            assert_curve(&flipped_result, &result);
+        }
+    }
+
+    #[test]
+    fn scale_and_translate_do_not_commute() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0],
+            x: vec![1.0, 2.0],
+            y: vec![-1.0, 3.0],
+        };
+
+        let scaled_first = curve.scale(2.0, 3.0).translate(1.0, 1.0);
+        let translated_first = curve.translate(1.0, 1.0).scale(2.0, 3.0);
+
+        assert_eq!(scaled_first.timestamps, curve.timestamps);
+        assert_eq!(scaled_first.x, vec![3.0, 5.0]);
+        assert_eq!(scaled_first.y, vec![-2.0, 10.0]);
+        assert_eq!(translated_first.x, vec![4.0, 6.0]);
+        assert_eq!(translated_first.y, vec![0.0, 12.0]);
+    }
+
+    #[test]
+    fn delimited_output_with_non_finite_values() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![1.5, f64::NAN, 3.0],
+            y: vec![f64::INFINITY, 2.0, f64::NEG_INFINITY],
+        };
+
+        assert_eq!(
+            curve.to_delimited_string(", ", ""),
+            "timestamps, x, y\n0, 1.5, \n1, , 2\n2, 3, \n"
+        );
+        assert_eq!(
+            curve.to_delimited_string(";", "null"),
+            "timestamps;x;y\n0;1.5;null\n1;null;2\n2;3;null\n"
+        );
+    }
+
+    #[test]
+    fn sanitize_removes_nan() {
+        let original = random_tolerance_curve();
+        assert!(!original.contains_nonfinite());
+
+        let mut curve = original.clone();
+        curve.y[3] = f64::NAN;
+        assert!(curve.contains_nonfinite());
+
+        let sanitized = curve.sanitize();
+        assert_eq!(sanitized.len(), original.len() - 1);
+        assert!(!sanitized.contains_nonfinite());
+        assert!(!sanitized.timestamps.contains(&original.timestamps[3]));
+    }
+
+    #[test]
+    fn resample_straight_line_by_arc_length() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 5.0, 6.0],
+            x: vec![0.0, 1.0, 3.0, 6.0],
+            y: vec![0.0, 2.0, 6.0, 12.0],
+        };
+
+        let result = curve.resample_by_arc_length(4).unwrap();
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(result.get_first(), curve.get_first());
+        assert_eq!(result.get_last(), curve.get_last());
+        assert_eq!(result.x, vec![0.0, 2.0, 4.0, 6.0]);
+        assert_eq!(result.y, vec![0.0, 4.0, 8.0, 12.0]);
+        assert_eq!(result.timestamps, vec![0.0, 3.0, 5.0 + 1.0 / 3.0, 6.0]);
+
+        assert!(curve.resample_by_arc_length(1).is_err());
+    }
+
+    #[test]
+    fn figure_eight_self_intersects() {
+        let figure_eight = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![0.0, 2.0, 2.0, 0.0, 0.0],
+            y: vec![0.0, 2.0, 0.0, 2.0, 0.0],
+        };
+
+        assert!(figure_eight.has_self_intersections());
+        assert_eq!(figure_eight.self_intersections(false), vec![(1.0, 1.0)]);
+
+        let arc = Curve::from_timestamp_fn(20, 0.0, std::f64::consts::PI, |t| (t.cos(), t.sin()));
+
+        assert!(!arc.has_self_intersections());
+    }
+
+    #[test]
+    fn close_appends_first_point() {
+        let mut square = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![0.0, 1.0, 1.0, 0.0, 0.0],
+            y: vec![0.0, 0.0, 1.0, 1.0, 0.0],
+        };
+
+        assert!(square.is_closed(0.0));
+        square.close(0.0);
+        assert_eq!(square.len(), 5);
+
+        let mut open = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 1.0],
+            y: vec![0.0, 0.0, 1.0],
+        };
+
+        assert!(!open.is_closed(1.0));
+        assert!(open.is_closed(1.5));
+        open.close(1.0);
+        assert_eq!(open.get_last(), Some((3.0, 0.0, 0.0)));
+
+        let mut line = Curve {
+            timestamps: vec![0.0, 2.0],
+            x: vec![0.0, 3.0],
+            y: vec![0.0, 4.0],
+        };
+
+        line.close(1.0);
+        assert_eq!(line.timestamps, vec![0.0, 2.0, 4.0]);
+        assert!(line.is_closed(0.0));
+    }
+
+    #[test]
+    fn simplify_angles_keeps_significant_turns() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            x: vec![0.0, 1.0, 2.0, 2.0, 2.0, 3.0],
+            y: vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0],
+        };
+
+        let result = curve.simplify_angles(89.0);
+
+        assert_eq!(result.timestamps, vec![0.0, 2.0, 5.0]);
+
+        let result = curve.simplify_angles(30.0);
+
+        assert_eq!(result.timestamps, vec![0.0, 2.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn segments_of_decimated_curve() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+        let result = decimate_to_count(&timestamps, &x, &y, 13).unwrap();
+
+        let mut segments = result.segments();
+
+        assert_eq!(segments.len(), 12);
+        assert_eq!(
+            segments.next(),
+            Some(((0.0, 77.0, 54.0), (33.0, 143.0, 111.0)))
+        );
+        assert_eq!(segments.len(), 11);
+        assert_eq!(segments.count(), 11);
+
+        assert_eq!(
+            Curve::from_vectors(vec![], vec![], vec![])
+                .unwrap()
+                .segments()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn window_iter_overlaps() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![5.0, 6.0, 7.0, 8.0, 9.0],
+            y: vec![10.0, 11.0, 12.0, 13.0, 14.0],
+        };
+
+        let windows: Vec<_> = curve.window_iter(3).collect();
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].0, &[0.0, 1.0, 2.0]);
+        assert_eq!(windows[1].1, &[6.0, 7.0, 8.0]);
+        assert_eq!(windows[2].2, &[12.0, 13.0, 14.0]);
+
+        assert_eq!(curve.window_iter(6).len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The window size must be at least two.")]
+    fn window_iter_too_small() {
+        let curve = random_tolerance_curve();
+        curve.window_iter(1);
+    }
+
+    #[test]
+    fn count_range_avoids_limit() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0];
+        let x = vec![0.0, 0.0, 1.0, 4.0];
+        let y = vec![0.0, 1.0, 0.0, 4.0];
+
+        let result = decimate_to_count_range(&timestamps, &x, &y, 3, 4).unwrap();
+
+        assert!((3..=4).contains(&result.len()));
+
+        assert!(decimate_to_count_range(&timestamps, &x, &y, 3, 2).is_err());
+    }
+
+    #[test]
+    fn sample_sorts_indices() {
+        let curve = random_tolerance_curve();
+
+        let result = curve.sample(&[10, 0, 5, 5]).unwrap();
+
+        assert_eq!(result.timestamps, vec![0.0, 41.0, 80.0]);
+        assert_eq!(result.x[1], curve.x[5]);
+        assert_eq!(
+            curve.sample(&[0, curve.len()]).unwrap_err(),
+            format!(
+                "Index {} is out of bounds for a curve with {} points.",
+                curve.len(),
+                curve.len()
+            )
+        );
+    }
+
+    #[test]
+    fn constant_velocity_gradient_magnitude() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 3.0, 4.0, 8.0],
+            x: vec![0.0, 3.0, 9.0, 12.0, 24.0],
+            y: vec![0.0, 4.0, 12.0, 16.0, 32.0],
+        };
+
+        for speed in curve.gradient_magnitude() {
+            assert!((speed - 5.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn time_span_and_range() {
+        let empty = Curve::from_vectors(vec![], vec![], vec![]).unwrap();
+        let single = Curve::from_vectors(vec![3.0], vec![0.0], vec![0.0]).unwrap();
+        let curve = random_tolerance_curve();
+
+        assert_eq!(empty.time_span(), None);
+        assert_eq!(empty.time_range(), None);
+        assert_eq!(single.time_span(), None);
+        assert_eq!(single.time_range(), Some((3.0, 3.0)));
+        assert_eq!(curve.time_span(), Some(648.0));
+        assert_eq!(curve.time_range(), Some((0.0, 648.0)));
+    }
+
+    #[test]
+    fn search_options_limits() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+
+        let options = SearchOptions::new(100, Some(20.0), Some(1.0));
+        let result = decimate_to_count_with_options(&timestamps, &x, &y, 13, &options).unwrap();
+
+        assert_curve(
+            &result,
+            &decimate_to_count(&timestamps, &x, &y, 13).unwrap(),
+        );
+
+        let options = SearchOptions::new(0, None, None);
+
+        assert!(decimate_to_count_with_options(&timestamps, &x, &y, 13, &options).is_err());
+    }
+
+    #[test]
+    fn deadband_collapses_small_zigzag() {
+        let mut curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            x: vec![0.0, 0.1, 0.0, 0.1, 0.0, 0.1],
+            y: vec![0.0, 0.1, 0.2, 0.1, 0.0, 0.1],
+        };
+
+        curve.apply_deadband(0.5);
+
+        assert_eq!(curve.timestamps, vec![0.0, 5.0]);
+        assert_eq!(curve.get_last(), Some((5.0, 0.1, 0.1)));
+    }
+
+    #[test]
+    fn timestamped_points_round_trip() {
+        let curve = random_tolerance_curve();
+
+        assert_curve(&Curve::from_timestamped_points(&curve.to_triples()), &curve);
+    }
+
+    #[test]
+    fn clamp_out_of_bounds_point() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![1.0, 12.0, 3.0],
+            y: vec![2.0, -4.0, 5.0],
+        };
+
+        let result = curve.clamp(0.0, 10.0, 0.0, 10.0).unwrap();
+
+        assert_eq!(result.timestamps, curve.timestamps);
+        assert_eq!(result.x, vec![1.0, 10.0, 3.0]);
+        assert_eq!(result.y, vec![2.0, 0.0, 5.0]);
+        assert!(curve.clamp(1.0, 0.0, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn convex_hull_excludes_interior_points() {
+        let square = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            x: vec![0.0, 2.0, 1.0, 2.0, 1.0, 0.0],
+            y: vec![0.0, 0.0, 1.0, 2.0, 2.0, 2.0],
+        };
+
+        let hull = square.compute_convex_hull();
+
+        assert_eq!(hull.timestamps, vec![0.0, 1.0, 3.0, 5.0]);
+
+        let triangle = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 4.0, 1.0, 0.0],
+            y: vec![0.0, 0.0, 1.0, 4.0],
+        };
+
+        let hull = triangle.compute_convex_hull();
+
+        assert_eq!(hull.timestamps, vec![0.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn polyline_reference_encoding() {
+        // the example from Google's Encoded Polyline Algorithm Format documentation
+        let encoded = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+        let curve = Curve::from_pairs(
+            vec![(-120.2, 38.5), (-120.95, 40.7), (-126.453, 43.252)],
+            0.0,
+            1.0,
+        );
+
+        assert_eq!(curve.to_polyline_string(5), encoded);
+
+        let decoded = Curve::decode_polyline(encoded, 5).unwrap();
+
+        assert_eq!(decoded.timestamps, vec![0.0, 1.0, 2.0]);
+        for i in 0..3 {
+            assert!((decoded.x[i] - curve.x[i]).abs() < 1e-9);
+            assert!((decoded.y[i] - curve.y[i]).abs() < 1e-9);
+        }
+
+        assert!(Curve::decode_polyline("_p~iF", 5).is_err());
+        assert!(Curve::decode_polyline("_p~i", 5).is_err());
+    }
+
+    #[test]
+    fn signed_curvature_degenerate_cases() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            x: vec![0.0, 1.0, 2.0, 2.0, 3.0, 2.0, 2.0],
+            y: vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0],
+        };
+
+        let curvature = curve.compute_signed_curvature();
+
+        assert_eq!(curvature.len(), 7);
+        assert_eq!(curvature[0], 0.0);
+        // collinear
+        assert_eq!(curvature[1], 0.0);
+        // repeated points
+        assert_eq!(curvature[2], 0.0);
+        assert_eq!(curvature[3], 0.0);
+        // a left turn on the unit circle around (2, 1)
+        assert!((curvature[4] - 1.0).abs() < 1e-12);
+        assert_eq!(curvature[5], 0.0);
+
+        let right_turn = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 2.0],
+            y: vec![0.0, 1.0, 0.0],
+        };
+
+        assert!((right_turn.compute_signed_curvature()[1] + 1.0).abs() < 1e-12);
+
+        let line = Curve::from_vectors(vec![0.0, 1.0], vec![0.0, 1.0], vec![0.0, 1.0]).unwrap();
+
+        assert_eq!(line.compute_signed_curvature(), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn weighted_tolerance_overrides_distance() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = vec![0.0, 0.01, 3.0, 0.0, 0.0];
+
+        let result = decimate_by_weighted_tolerance(
+            &timestamps,
+            &x,
+            &y,
+            &[1.0, 1000.0, 0.1, f64::INFINITY, 1.0],
+            1.0,
+        )
+        .unwrap();
+
+        assert_eq!(result.timestamps, vec![0.0, 1.0, 3.0, 4.0]);
+
+        let result = decimate_by_weighted_tolerance(&timestamps, &x, &y, &[1.0; 5], 1.0).unwrap();
+
+        assert_eq!(result.timestamps, vec![0.0, 2.0, 4.0]);
+        assert!(decimate_by_weighted_tolerance(&timestamps, &x, &y, &[1.0; 4], 1.0).is_err());
+    }
+
+    #[test]
+    fn autocorrelation_at_zero_lag() {
+        let curve = random_tolerance_curve();
+
+        let autocorrelation = curve.compute_autocorrelation(5).unwrap();
+
+        assert_eq!(autocorrelation.len(), 6);
+        assert!((autocorrelation[0] - 1.0).abs() < 1e-12);
+        assert!(autocorrelation[1] < 1.0);
+
+        let constant = Curve::from_pairs(vec![(0.0, 2.0); 4], 0.0, 1.0);
+
+        assert_eq!(constant.compute_autocorrelation(3).unwrap(), vec![1.0; 4]);
+        assert!(constant.compute_autocorrelation(4).is_err());
+    }
+
+    #[test]
+    fn snap_to_grid_merges_points() {
+        let curve = Curve {
+            timestamps: (0..10).map(|t| t as f64).collect(),
+            x: vec![9.0, 10.5, 11.0, 9.6, 10.2, 14.0, 21.0, 19.0, 10.1, 9.9],
+            y: vec![10.4, 9.5, 10.0, 11.0, 9.8, 10.0, 10.0, 11.0, 10.0, 10.0],
+        };
+
+        let result = curve.snap_to_grid(10.0).unwrap();
+
+        assert_eq!(result.timestamps, vec![0.0, 6.0, 8.0]);
+        assert_eq!(result.x, vec![10.0, 20.0, 10.0]);
+        assert_eq!(result.y, vec![10.0, 10.0, 10.0]);
+        assert!(curve.snap_to_grid(0.0).is_err());
+    }
+
+    #[test]
+    fn distance_to_point_on_segments() {
+        let triangle = Curve {
+            timestamps: vec![0.0, 1.0],
+            x: vec![3.0, 0.0],
+            y: vec![0.0, 4.0],
+        };
+
+        assert_eq!(triangle.distance_to_point(1.5, 2.0), 0.0);
+        assert!((triangle.distance_to_point(0.0, 0.0) - 3.0 * 4.0 / 5.0).abs() < 1e-12);
+        assert_eq!(triangle.distance_to_point(6.0, -4.0), 5.0);
+    }
+
+    #[test]
+    fn epsilon_schedule_matches_single_tolerances() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+        let tolerances = [10.0, 0.5, 3.0];
+
+        let curves =
+            douglas_peucker_with_epsilon_schedule(&timestamps, &x, &y, &tolerances).unwrap();
+
+        assert_eq!(curves.len(), 3);
+
+        for (curve, tolerance) in curves.iter().zip([0.5, 3.0, 10.0]) {
+            assert_curve(
+                curve,
+                &decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap(),
+            );
+        }
+
+        for pair in curves.windows(2) {
+            assert!(pair[1]
+                .timestamps
+                .iter()
+                .all(|t| pair[0].timestamps.contains(t)));
+        }
+
+        let curve = Curve { timestamps, x, y };
+
+        for (method, function) in curve
+            .douglas_peucker_with_epsilon_schedule(&tolerances)
+            .iter()
+            .zip(&curves)
+        {
+            assert_curve(method, function);
+        }
+
+        let single = Curve::from_pairs(vec![(1.0, 2.0)], 0.0, 1.0);
+        let curves = single.douglas_peucker_with_epsilon_schedule(&[1.0, 2.0]);
+
+        assert_eq!(curves.len(), 2);
+        assert_curve(&curves[1], &single);
+    }
+
+    #[test]
+    fn shift_and_scale_timestamps() {
+        let curve = random_tolerance_curve().shift_timestamps(1000.0);
+
+        let shifted = curve.shift_timestamps(-curve.timestamps[0]);
+
+        assert_eq!(shifted.timestamps[0], 0.0);
+        assert_eq!(shifted.x, curve.x);
+
+        let scaled = curve.scale_timestamps(0.5).unwrap();
+
+        assert_eq!(scaled.time_span(), curve.time_span().map(|span| span / 2.0));
+        assert!(curve.scale_timestamps(0.0).is_err());
+    }
+
+    #[test]
+    fn exponential_smoothing() {
+        let curve = random_tolerance_curve();
+
+        assert_curve(&curve.smooth_exponential(1.0).unwrap(), &curve);
+
+        let step = Curve::from_pairs(vec![(0.0, 0.0), (4.0, 8.0), (4.0, 8.0)], 0.0, 1.0);
+        let smoothed = step.smooth_exponential(0.5).unwrap();
+
+        assert_eq!(smoothed.x, vec![0.0, 2.0, 3.0]);
+        assert_eq!(smoothed.y, vec![0.0, 4.0, 6.0]);
+        assert!(curve.smooth_exponential(0.0).is_err());
+        assert!(curve.smooth_exponential(1.5).is_err());
+    }
+
+    #[test]
+    fn decimate_loop_with_coincident_endpoints() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 5.0, 5.0, 0.0, 0.0];
+        let y = vec![0.0, 0.0, 5.0, 5.0, 0.0];
+
+        let result = decimate_by_tolerance(&timestamps, &x, &y, 1.0).unwrap();
+
+        assert_eq!(result.timestamps, timestamps);
+    }
+
+    #[test]
+    fn heading_of_north_and_east_tracks() {
+        let north =
+            Curve::from_vectors(vec![0.0, 1.0, 2.0], vec![1.0; 3], vec![0.0, 1.0, 2.0]).unwrap();
+        let east =
+            Curve::from_vectors(vec![0.0, 1.0, 2.0], vec![0.0, 1.0, 2.0], vec![1.0; 3]).unwrap();
+        let west = east.reversed();
+
+        for i in 0..3 {
+            assert_eq!(north.heading_at(i), Some(0.0));
+            assert_eq!(east.heading_at(i), Some(90.0));
+            assert_eq!(west.heading_at(i), Some(270.0));
+        }
+
+        assert_eq!(north.heading_at(3), None);
+        assert_eq!(
+            Curve::from_vectors(vec![0.0], vec![0.0], vec![0.0])
+                .unwrap()
+                .heading_at(0),
+            None
+        );
+    }
+
+    #[test]
+    fn turning_angles_of_closed_hull() {
+        let mut hull = Curve::from_timestamp_fn(10, 0.0, 9.0, |t| {
+            let angle = t * std::f64::consts::TAU / 9.0;
+            (angle.cos() * 3.0, angle.sin() * 2.0)
+        })
+        .compute_convex_hull();
+
+        // walk past the start again so every vertex has both neighbours
+        for i in 0..2 {
+            let t = hull.timestamps[hull.len() - 1] + 1.0;
+            hull.timestamps.push(t);
+            hull.x.push(hull.x[i]);
+            hull.y.push(hull.y[i]);
+        }
+
+        let total: f64 = hull.compute_turning_angles().iter().sum();
+
+        assert!((total - std::f64::consts::TAU).abs() < 1e-9);
+
+        let reversal = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 0.0)], 0.0, 1.0);
+
+        assert_eq!(
+            reversal.compute_turning_angles(),
+            vec![0.0, std::f64::consts::PI, 0.0]
+        );
+    }
+
+    #[test]
+    fn projection_onto_segment_is_clamped() {
+        let curve = Curve::from_pairs(vec![(1.0, 3.0), (-2.0, 0.0), (5.0, 1.0)], 0.0, 1.0);
+
+        assert_eq!(
+            curve.project_onto_segment(0.0, 0.0, 2.0, 0.0),
+            vec![0.5, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn uniform_timestamps() {
+        let uniform = Curve::from_pairs(vec![(1.0, 2.0), (3.0, 4.0), (5.0, 6.0)], 10.0, 0.5);
+
+        assert!(uniform.timestamps_are_uniform(0.0));
+        assert!(!random_tolerance_curve().timestamps_are_uniform(0.5));
+        assert_eq!(
+            uniform.strip_timestamps(),
+            (vec![1.0, 3.0, 5.0], vec![2.0, 4.0, 6.0])
+        );
+    }
+
+    #[test]
+    fn distances_of_pruned_points_to_chord() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.9, 4.0, 5.0, 4.0];
+        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
+        let curve = Curve::from_vectors(timestamps.clone(), x.clone(), y.clone()).unwrap();
+
+        // everything in between is pruned at this tolerance
+        assert_eq!(
+            decimate_by_tolerance(&timestamps, &x, &y, 100.0)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let distances = curve.perpendicular_distances_to_chord();
+
+        assert_eq!(distances.len(), 5);
+        assert_eq!(distances[0], 0.0);
+        assert_eq!(distances[4], 0.0);
+        assert!(distances[1..4].iter().all(|&d| d > 0.0));
+    }
+
+    #[test]
+    fn memory_budget_limits_points() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+
+        let result = decimate_to_memory_budget(&timestamps, &x, &y, 13 * 24 + 23).unwrap();
+
+        assert_curve(
+            &result,
+            &decimate_to_count(&timestamps, &x, &y, 13).unwrap(),
+        );
+        assert!(decimate_to_memory_budget(&timestamps, &x, &y, 47).is_err());
+    }
+
+    #[test]
+    fn merge_near_duplicate_points() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![0.0, 5.0, 5.0, 5.0, 10.0],
+            y: vec![0.0, 5.0, 5.02, 5.04, 0.0],
+        };
+
+        let result = curve.merge_nearby_points(0.1);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get_first(), curve.get_first());
+        assert_eq!(result.get_last(), curve.get_last());
+        // the first pair is merged before the third point joins it
+        assert_eq!(result.timestamps[1], 2.25);
+        assert_eq!(result.x[1], 5.0);
+    }
+
+    #[test]
+    fn histogram_of_uniform_values() {
+        let curve = Curve::from_timestamp_fn(100, 0.0, 99.0, |t| (0.0, t));
+
+        let histogram = curve.compute_histogram(4, Channel::Y).unwrap();
+
+        assert_eq!(histogram.len(), 4);
+        assert!((histogram.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(histogram.iter().all(|f| (f - 0.25).abs() < 1e-12));
+
+        assert_eq!(
+            curve.compute_histogram(2, Channel::X).unwrap(),
+            vec![1.0, 0.0]
+        );
+        assert!(curve.compute_histogram(0, Channel::Y).is_err());
+        assert!(Curve::from_pairs(vec![], 0.0, 1.0)
+            .compute_histogram(4, Channel::Y)
+            .is_err());
+    }
+
+    #[test]
+    fn correlation_with_self_and_negation() {
+        let curve = random_tolerance_curve();
+        let negated = Curve {
+            timestamps: curve.timestamps.clone(),
+            x: curve.x.clone(),
+            y: curve.y.iter().map(|y| -y).collect(),
+        };
+
+        assert!((curve.time_series_correlation(&curve).unwrap() - 1.0).abs() < 1e-12);
+        assert!((curve.time_series_correlation(&negated).unwrap() + 1.0).abs() < 1e-12);
+
+        let later = curve.shift_timestamps(1000.0);
+
+        assert!(curve.time_series_correlation(&later).is_err());
+    }
+
+    #[test]
+    fn offset_straight_line_and_corner() {
+        let line = Curve::from_pairs(vec![(0.0, 2.0), (1.0, 2.0), (5.0, 2.0)], 0.0, 1.0);
+
+        let offset = line.offset_curve(1.0);
+
+        assert_eq!(offset.timestamps, line.timestamps);
+        assert_eq!(offset.x, line.x);
+        assert_eq!(offset.y, vec![3.0, 3.0, 3.0]);
+
+        // a left turn around the corner at (2, 0)
+        let corner = Curve::from_pairs(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)], 0.0, 1.0);
+        let offset = corner.offset_curve(1.0);
+
+        assert!((offset.x[1] - 1.0).abs() < 1e-12);
+        assert!((offset.y[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parametric_form_of_straight_line() {
+        let line = Curve::from_pairs(
+            vec![(0.0, 0.0), (3.0, 4.0), (6.0, 8.0), (9.0, 12.0)],
+            0.0,
+            1.0,
+        );
+
+        let (arc_lengths, xy) = line.to_parametric_form();
+
+        assert_eq!(arc_lengths, vec![0.0, 5.0, 10.0, 15.0]);
+        assert_eq!(xy, vec![0.0, 0.0, 3.0, 4.0, 6.0, 8.0, 9.0, 12.0]);
+    }
+
+    #[test]
+    fn simplify_by_angle_and_distance_needs_both() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            // a gentle bend at t = 1 and a sharp but tiny spike at t = 3
+            x: vec![0.0, 5.0, 10.0, 10.2, 10.4, 20.0],
+            y: vec![0.0, 0.05, 0.0, 0.3, 0.0, 0.0],
+        };
+
+        let result = curve.simplify_by_angle_and_distance(30.0, 0.5);
+
+        assert_eq!(result.timestamps, vec![0.0, 2.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn cluster_back_and_forth_points() {
+        let curve = Curve::from_pairs(
+            vec![(0.0, 0.0), (10.0, 0.0), (0.5, 0.0), (1.0, 0.0), (10.2, 0.0)],
+            0.0,
+            1.0,
+        );
+
+        assert_eq!(curve.cluster_points(0.6), vec![vec![0, 2, 3], vec![1, 4]]);
+        assert_eq!(curve.cluster_points(0.0).len(), 5);
+    }
+
+    #[test]
+    fn constant_velocity_speeds() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 3.0, 4.0],
+            x: vec![0.0, 3.0, 9.0, 12.0],
+            y: vec![0.0, 4.0, 12.0, 16.0],
+        };
+
+        assert_eq!(curve.compute_average_speed(), Some(5.0));
+        assert_eq!(curve.compute_max_speed(), Some(5.0));
+
+        let single = Curve::from_vectors(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+
+        assert_eq!(single.compute_average_speed(), None);
+        assert_eq!(single.compute_max_speed(), None);
+    }
+
+    #[test]
+    fn interpolate_to_original_and_new_timestamps() {
+        let curve = random_tolerance_curve();
+
+        assert_curve(
+            &curve.interpolate_to_timestamps(&curve.timestamps).unwrap(),
+            &curve,
+        );
+
+        let line = Curve::from_pairs(vec![(0.0, 0.0), (10.0, 20.0)], 0.0, 10.0);
+        let result = line.interpolate_to_timestamps(&[2.5, 5.0]).unwrap();
+
+        assert_eq!(result.x, vec![2.5, 5.0]);
+        assert_eq!(result.y, vec![5.0, 10.0]);
+        assert!(line.interpolate_to_timestamps(&[5.0, 2.5]).is_err());
+        assert!(line.interpolate_to_timestamps(&[11.0]).is_err());
+    }
+
+    #[test]
+    fn raw_pointers_read_back_values() {
+        let curve = random_tolerance_curve();
+        let (mut t, mut x, mut y) = (std::ptr::null(), std::ptr::null(), std::ptr::null());
+
+        let len = unsafe { rdp_curve_data(&curve, &mut t, &mut x, &mut y) };
+
+        assert_eq!(len, curve.len());
+        unsafe {
+            assert_eq!(std::slice::from_raw_parts(t, len), &curve.timestamps[..]);
+            assert_eq!(std::slice::from_raw_parts(x, len), &curve.x[..]);
+            assert_eq!(std::slice::from_raw_parts(y, len), &curve.y[..]);
+        }
+    }
+
+    #[test]
+    fn winding_number_of_unit_square() {
+        let square = Curve::from_pairs(
+            vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)],
+            0.0,
+            1.0,
+        );
+
+        assert_eq!(square.compute_winding_number(0.5, 0.5), 1);
+        assert_eq!(square.compute_winding_number(0.1, 0.9), 1);
+        assert_eq!(square.reversed().compute_winding_number(0.5, 0.5), -1);
+        assert_eq!(square.compute_winding_number(1.5, 0.5), 0);
+        assert_eq!(square.compute_winding_number(0.5, -0.1), 0);
+        assert_eq!(square.compute_winding_number(-3.0, 2.0), 0);
+    }
+
+    #[test]
+    fn fractal_dimension_of_line_and_hilbert_curve() {
+        let line = Curve::from_pairs(vec![(0.0, 0.0), (100.0, 100.0)], 0.0, 1.0);
+        let dimension = line.compute_fractal_dimension(10);
+
+        assert!((dimension - 1.0).abs() < 0.1);
+
+        // an order 6 Hilbert curve through the centres of a 64 by 64 grid
+        let side = 64;
+        let hilbert = Curve::from_pairs(
+            (0..side * side)
+                .map(|d| {
+                    let (mut x, mut y, mut t) = (0, 0, d);
+                    let mut s = 1;
+
+                    while s < side {
+                        let rx = 1 & (t / 2);
+                        let ry = 1 & (t ^ rx);
+
+                        if ry == 0 {
+                            if rx == 1 {
+                                x = s - 1 - x;
+                                y = s - 1 - y;
+                            }
+                            std::mem::swap(&mut x, &mut y);
+                        }
+
+                        x += s * rx;
+                        y += s * ry;
+                        t /= 4;
+                        s *= 2;
+                    }
+
+                    (x as f64 + 0.5, y as f64 + 0.5)
+                })
+                .collect(),
+            0.0,
+            1.0,
+        );
+        let dimension = hilbert.compute_fractal_dimension(16);
+
+        assert!(dimension > 1.9);
+        assert!(line.compute_fractal_dimension(1).is_nan());
+        assert!(Curve::from_pairs(vec![], 0.0, 1.0)
+            .compute_fractal_dimension(16)
+            .is_nan());
+    }
+
+    #[test]
+    fn narrow_gaussian_keeps_values() {
+        let curve = random_tolerance_curve();
+
+        let smoothed = curve.smooth_gaussian(0.0001, 3).unwrap();
+
+        assert_curve(&smoothed, &curve);
+
+        let spike = Curve::from_pairs(
+            vec![(0.0, 0.0), (0.0, 0.0), (0.0, 4.0), (0.0, 0.0), (0.0, 0.0)],
+            0.0,
+            1.0,
+        );
+        let smoothed = spike.smooth_gaussian(1.0, 3).unwrap();
+
+        assert!(smoothed.y[2] < 4.0 && smoothed.y[1] > 0.0);
+        assert!(curve.smooth_gaussian(1.0, 4).is_err());
+        assert!(curve.smooth_gaussian(0.0, 3).is_err());
+        assert!(spike.smooth_gaussian(1.0, 5).is_err());
+    }
+
+    #[test]
+    fn fixed_capacity_decimation() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+        let mut output = FixedCapacityCurve::<64>::new();
+
+        decimate_by_tolerance_fixed(&timestamps, &x, &y, 5.0, &mut output).unwrap();
+
+        assert_curve(
+            &output.to_curve(),
+            &decimate_by_tolerance(&timestamps, &x, &y, 5.0).unwrap(),
+        );
+
+        let mut small = FixedCapacityCurve::<3>::new();
+
+        assert!(decimate_by_tolerance_fixed(&timestamps, &x, &y, 5.0, &mut small).is_err());
+
+        let (timestamps, x, y) = (
+            [0.0, 1.0, 2.0, 3.0],
+            [0.0, f64::NAN, 2.0, 3.0],
+            [0.0, 1.0, 5.0, 0.0],
+        );
+
+        decimate_by_tolerance_fixed(&timestamps, &x, &y, 1.0, &mut output).unwrap();
+
+        assert_eq!(
+            output.to_curve().timestamps,
+            decimate_by_tolerance(&timestamps, &x, &y, 1.0)
+                .unwrap()
+                .timestamps
+        );
+    }
+
+    #[test]
+    fn fixed_capacity_insert_and_push() {
+        let mut curve = FixedCapacityCurve::<3>::default();
+
+        assert!(curve.is_empty());
+        curve.push(0.0, 1.0, 2.0).unwrap();
+        curve.push(2.0, 5.0, 6.0).unwrap();
+        curve.insert(1, 1.0, 3.0, 4.0).unwrap();
+
+        assert_eq!(curve.len(), 3);
+        assert_eq!(curve.get_point(1), Some((1.0, 3.0, 4.0)));
+        assert_eq!(curve.get_point(3), None);
+        assert!(curve.push(3.0, 0.0, 0.0).is_err());
+        assert_eq!(curve.to_curve().x, vec![1.0, 3.0, 5.0]);
+    }
+
+    #[test]
+    fn subsample_one_hertz_curve() {
+        let curve = Curve::from_timestamp_fn(100, 0.0, 99.0, |t| (t, t.sin()));
+
+        let result = curve.subsample_by_time(5.0).unwrap();
+
+        // every fifth second plus the last point
+        assert_eq!(result.len(), 21);
+        assert_eq!(result.timestamps[1], 5.0);
+        assert_eq!(result.get_last(), curve.get_last());
+        assert!(curve.subsample_by_time(0.0).is_err());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    proptest::proptest! {
+        #[test]
+        fn recursive_matches_iterative(
+            points in proptest::collection::vec((0.0..100.0f64, 0.0..100.0f64), 2..60),
+            tolerance in -1.0..30.0f64,
+        ) {
+            let timestamps: Vec<f64> = (0..points.len()).map(|t| t as f64).collect();
+            let (x, y): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+            let curve = Curve::from_vectors(timestamps.clone(), x.clone(), y.clone()).unwrap();
+
+            assert_curve(
+                &curve.apply_douglas_peucker_recursive(tolerance, curve.len()).unwrap(),
+                &decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn recursion_limit_counts_splits() {
+        let curve = random_tolerance_curve();
+
+        assert!(curve.apply_douglas_peucker_recursive(0.0, 2).is_err());
+        // a straight line never splits, so it needs no recursion at all
+        let line = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)], 0.0, 1.0);
+        assert_eq!(
+            line.apply_douglas_peucker_recursive(0.5, 0).unwrap().len(),
+            2
+        );
+        // a negative tolerance keeps every point, like `decimate_by_tolerance`
+        assert_eq!(
+            line.apply_douglas_peucker_recursive(-1.0, 2).unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn length_ratio_of_line_and_arc() {
+        let line = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)], 0.0, 1.0);
+        let arc = Curve::from_timestamp_fn(30, 0.0, std::f64::consts::PI, |t| (t.cos(), t.sin()));
+
+        assert!((line.compute_length_ratio() - 1.0).abs() < 1e-12);
+        assert!(arc.compute_length_ratio() > 1.5);
+
+        let closed = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 0.0)], 0.0, 1.0);
+
+        assert_eq!(closed.compute_length(), 2.0);
+        assert_eq!(closed.compute_length_ratio(), f64::INFINITY);
+    }
+
+    #[test]
+    fn returned_tolerance_reproduces_curve() {
+        let Curve { timestamps, x, y } = random_tolerance_curve();
+
+        let (curve, tolerance) = decimate_to_count_with_tolerance(&timestamps, &x, &y, 13).unwrap();
+
+        assert_eq!(curve.len(), 13);
+        assert_curve(
+            &decimate_by_tolerance(&timestamps, &x, &y, tolerance).unwrap(),
+            &curve,
+        );
+    }
+
+    #[test]
+    fn straightness_of_half_circle() {
+        let half_circle =
+            Curve::from_timestamp_fn(1000, 0.0, std::f64::consts::PI, |t| (t.cos(), t.sin()));
+        let line = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)], 0.0, 1.0);
+        let closed = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 0.0)], 0.0, 1.0);
+
+        assert!(
+            (half_circle.compute_path_straightness() - 2.0 / std::f64::consts::PI).abs() < 1e-5
+        );
+        assert!((line.compute_path_straightness() - 1.0).abs() < 1e-12);
+        assert_eq!(closed.compute_path_straightness(), 0.0);
+    }
+
+    #[test]
+    fn keyframes_survive_decimation() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = vec![0.0, 0.5, 1.0, 1.5, 2.0];
+
+        let result = simplify_to_keyframes(&timestamps, &x, &y, &[1.0, 3.0], 100.0).unwrap();
+
+        assert_eq!(result.timestamps, vec![0.0, 1.0, 3.0, 4.0]);
+        assert_eq!(result.y, vec![0.0, 0.5, 1.5, 2.0]);
+        assert!(simplify_to_keyframes(&timestamps, &x, &y, &[1.5], 100.0).is_err());
+    }
+
+    #[test]
+    fn energy_grows_with_curvature() {
+        let line = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)], 0.0, 1.0);
+        let gentle = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 0.2), (2.0, 0.0)], 0.0, 1.0);
+        let sharp = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)], 0.0, 1.0);
+
+        assert_eq!(line.compute_energy(), 0.0);
+        assert!(sharp.compute_energy() > gentle.compute_energy());
+        assert!(gentle.compute_energy() > 0.0);
+    }
+
+    #[test]
+    fn quarter_circle_scan() {
+        let angles: Vec<f64> = (0..3)
+            .map(|i| i as f64 * std::f64::consts::FRAC_PI_4)
+            .collect();
+
+        let curve = Curve::from_angle_distance(&angles, &[2.0, 2.0, 2.0], 1.0, -1.0).unwrap();
+
+        assert_eq!(curve.timestamps, vec![0.0, 1.0, 2.0]);
+        let expected = [
+            (3.0, -1.0),
+            (1.0 + 2f64.sqrt(), -1.0 + 2f64.sqrt()),
+            (1.0, 1.0),
+        ];
+        for (i, (x, y)) in expected.iter().enumerate() {
+            assert!((curve.x[i] - x).abs() < 1e-12);
+            assert!((curve.y[i] - y).abs() < 1e-12);
+        }
+
+        assert!(Curve::from_angle_distance(&angles, &[1.0], 0.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn density_counts_removed_points() {
+        let curve = random_tolerance_curve();
+        let retained = douglas_peucker_mask(
+            &curve.timestamps,
+            &curve.x,
+            &curve.y,
+            curve.diagonal_tolerance(),
+        )
+        .unwrap();
+
+        let density = curve.compute_point_density();
+
+        assert_eq!(density.len(), curve.len());
+        assert_eq!(
+            (
+                density[0],
+                density[17],
+                density[28],
+                density[52],
+                density[76]
+            ),
+            (16.0, 10.0, 23.0, 23.0, 0.0)
+        );
+        assert_eq!(
+            density.iter().sum::<f64>() as usize,
+            retained.iter().filter(|&&keep| !keep).count()
+        );
+
+        for (i, &keep) in retained.iter().enumerate() {
+            if !keep {
+                assert_eq!(density[i], 0.0);
+            }
+        }
+
+        assert_eq!(
+            Curve::from_pairs(vec![(0.0, 0.0)], 0.0, 1.0).compute_point_density(),
+            vec![0.0]
+        );
+    }
+
+    #[test]
+    fn angle_histogram_of_circle_and_shuttle() {
+        let circle =
+            Curve::from_timestamp_fn(361, 0.0, std::f64::consts::TAU, |t| (t.cos(), t.sin()));
+
+        let histogram = circle.compute_angle_histogram(4).unwrap();
+
+        assert!(histogram.iter().all(|f| (f - 0.25).abs() < 0.01));
+
+        // up and down the y axis
+        let shuttle = Curve::from_pairs(
+            (0..40)
+                .map(|i| {
+                    (
+                        0.0,
+                        if (i / 10) % 2 == 0 {
+                            i % 10
+                        } else {
+                            10 - i % 10
+                        } as f64,
+                    )
+                })
+                .collect(),
+            0.0,
+            1.0,
+        );
+
+        let histogram = shuttle.compute_angle_histogram(4).unwrap();
+
+        assert!(histogram[0] > 0.45 && histogram[2] > 0.45);
+        assert_eq!(histogram[1] + histogram[3], 0.0);
+        assert!(shuttle.compute_angle_histogram(0).is_err());
+    }
+
+    #[test]
+    fn rotate_quarter_turn() {
+        let curve = Curve::from_pairs(vec![(1.0, 0.0), (0.0, 2.0)], 0.0, 1.0);
+
+        let rotated = curve.rotate(std::f64::consts::FRAC_PI_2);
+
+        assert!(rotated.x[0].abs() < 1e-15 && (rotated.y[0] - 1.0).abs() < 1e-15);
+        assert!((rotated.x[1] + 2.0).abs() < 1e-15 && rotated.y[1].abs() < 1e-15);
+
+        let sheared = curve.apply_affine_transform(1.0, 1.0, 0.0, 1.0, 3.0, -1.0);
+
+        assert_eq!(sheared.x, vec![4.0, 5.0]);
+        assert_eq!(sheared.y, vec![-1.0, 1.0]);
+        assert_eq!(sheared.timestamps, curve.timestamps);
+    }
+
+    #[test]
+    fn nearest_neighbor_distances() {
+        let cluster = Curve::from_pairs(vec![(1.0, 1.0); 4], 0.0, 1.0);
+
+        assert_eq!(cluster.compute_nearest_neighbor_distances(), vec![0.0; 4]);
+
+        let grid = Curve::from_pairs(
+            (0..9)
+                .map(|i| ((i % 3) as f64 * 2.5, (i / 3) as f64 * 2.5))
+                .collect(),
+            0.0,
+            1.0,
+        );
+
+        assert_eq!(grid.compute_nearest_neighbor_distances(), vec![2.5; 9]);
+    }
+
+    #[test]
+    fn quality_report_finds_largest_error() {
+        let timestamps = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = vec![0.0, 1.0, 0.0, -3.0, 0.0];
+        let decimated = Curve::from_pairs(vec![(0.0, 0.0), (4.0, 0.0)], 0.0, 4.0);
+
+        let report = quality_report(&timestamps, &x, &y, &decimated).unwrap();
+
+        assert_eq!(report.max_error, 3.0);
+        assert_eq!(report.max_error_at_timestamp, 3.0);
+        assert_eq!(report.mean_error, 0.8);
+        assert!((report.error_stddev - 1.16619037896906).abs() < 1e-12);
+
+        let short = Curve::from_pairs(vec![(0.0, 0.0), (2.0, 0.0)], 0.0, 2.0);
+
+        assert!(quality_report(&timestamps, &x, &y, &short).is_err());
+    }
+
+    #[test]
+    fn densify_bounds_segment_lengths() {
+        let curve = random_tolerance_curve();
+        let decimated = decimate_to_count(&curve.timestamps, &curve.x, &curve.y, 13).unwrap();
+
+        let dense = decimated.densify(7.5).unwrap();
+
+        assert!(dense
+            .segments()
+            .all(|((_, x0, y0), (_, x1, y1))| distance(x0, y0, x1, y1) <= 7.5 + 1e-9));
+        assert_eq!(dense.get_first(), decimated.get_first());
+        assert_eq!(dense.get_last(), decimated.get_last());
+        assert!(dense.timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(decimated.densify(0.0).is_err());
+    }
+
+    #[test]
+    fn sample_halfway_along_path() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 3.0],
+            x: vec![0.0, 1.0, 1.0],
+            y: vec![0.0, 0.0, 3.0],
+        };
+
+        assert_eq!(curve.sample_at_fraction(0.0), curve.get_first());
+        assert_eq!(
+            curve.sample_at_fraction(0.5),
+            Some((1.0 + 2.0 / 3.0, 1.0, 1.0))
+        );
+        assert_eq!(curve.sample_at_fraction(1.0), curve.get_last());
+        assert_eq!(curve.sample_at_fraction(1.5), None);
+    }
+
+    #[test]
+    fn in_place_matches_allocating_decimation() {
+        let mut curve = random_tolerance_curve();
+        let expected = decimate_by_tolerance(&curve.timestamps, &curve.x, &curve.y, 4.0).unwrap();
+
+        decimate_by_tolerance_in_place(&mut curve, 4.0).unwrap();
+
+        assert_curve(&curve, &expected);
+    }
+
+    #[test]
+    fn in_place_with_negative_tolerance_keeps_every_point() {
+        let mut line = Curve::from_vectors(
+            vec![0.0, 1.0, 2.0],
+            vec![0.0, 1.0, 2.0],
+            vec![0.0, 1.0, 2.0],
+        )
+        .unwrap();
+        let expected = decimate_by_tolerance(&line.timestamps, &line.x, &line.y, -1.0).unwrap();
+
+        decimate_by_tolerance_in_place(&mut line, -1.0).unwrap();
+
+        assert_eq!(line.len(), 3);
+        assert_curve(&line, &expected);
+    }
+
+    #[test]
+    fn monotone_line_and_extremum() {
+        let line = Curve::from_vectors(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            vec![0.0, 0.5, 1.0, 1.5, 2.0],
+        )
+        .unwrap();
+
+        assert!(line.is_monotone_in_x());
+        assert!(line.is_monotone_in_y());
+        assert!(line.reversed().is_monotone_in_y());
+
+        let peak = Curve::from_pairs(vec![(0.0, 0.0), (1.0, 2.0), (2.0, 1.0)], 0.0, 1.0);
+
+        assert!(peak.is_monotone_in_x());
+        assert!(!peak.is_monotone_in_y());
+    }
+
+    #[test]
+    fn visvalingam_whyatt_removes_smallest_triangles() {
+        let timestamps = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let x = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = [0.0, 0.1, 3.0, 0.2, 0.0];
+
+        let curve = visvalingam_whyatt_to_count(&timestamps, &x, &y, 3).unwrap();
+
+        assert_curve(
+            &curve,
+            &Curve {
+                timestamps: vec![0.0, 2.0, 4.0],
+                x: vec![0.0, 2.0, 4.0],
+                y: vec![0.0, 3.0, 0.0],
+            },
+        );
+        assert!(visvalingam_whyatt_to_count(&timestamps, &x, &y, 1).is_err());
+    }
+
+    #[test]
+    fn decimate_adaptive_picks_algorithm() {
+        let mut state = 7;
+        let timestamps: Vec<f64> = (0..200).map(|i| i as f64).collect();
+        let noisy: Vec<f64> = (0..200).map(|_| next_random(&mut state) * 5.0).collect();
+        let (curve, name) = decimate_adaptive(&timestamps, &timestamps, &noisy, 20).unwrap();
+        assert_eq!(name, "visvalingam-whyatt");
+        assert_eq!(curve.len(), 20);
+
+        let angles: Vec<f64> = timestamps.iter().map(|t| t * 0.8).collect();
+        let x: Vec<f64> = angles.iter().map(|a| a.cos()).collect();
+        let y: Vec<f64> = angles.iter().map(|a| a.sin()).collect();
+        let (curve, name) = decimate_adaptive(&timestamps, &x, &y, 101).unwrap();
+        assert_eq!(name, "lang");
+        assert_eq!(curve.len(), 101);
+
+        let y: Vec<f64> = timestamps.iter().map(|t| (t / 40.0).sin() * 10.0).collect();
+        let (curve, name) = decimate_adaptive(&timestamps, &timestamps, &y, 20).unwrap();
+        assert_eq!(name, "ramer-douglas-peucker");
+        assert_eq!(curve.len(), 20);
+    }
+
+    #[test]
+    fn check_integrity_reports_every_violation() {
+        let curve = Curve {
+            timestamps: vec![0.0, 2.0, 1.0, f64::NAN],
+            x: vec![0.0, 0.0, f64::INFINITY],
+            y: vec![0.0, 0.0, 1.0],
+        };
+
+        assert_eq!(
+            curve.check_integrity(),
+            vec![
+                "Timestamps, x and y have different lengths (4, 3 and 3).",
+                "Timestamp at index 3 is NaN.",
+                "X at index 2 is inf.",
+                "Timestamp at index 2 (1) does not come after the previous one (2).",
+                "Segment between indices 0 and 1 has zero length.",
             ]
+        );
+        assert!(random_tolerance_curve().check_integrity().is_empty());
+    }
+
+    #[test]
+    fn mask_matches_decimation() {
+        let curve = random_tolerance_curve();
+        let tolerance = 3.0;
+        let mask = douglas_peucker_mask(&curve.timestamps, &curve.x, &curve.y, tolerance).unwrap();
+        let decimated =
+            decimate_by_tolerance(&curve.timestamps, &curve.x, &curve.y, tolerance).unwrap();
+
+        assert_eq!(mask.len(), curve.len());
+        assert!(mask[0] && mask[mask.len() - 1]);
+
+        let retained: Vec<f64> = (0..curve.len())
+            .filter(|&i| mask[i])
+            .map(|i| curve.timestamps[i])
+            .collect();
+        assert_eq!(retained, decimated.timestamps);
+    }
+
+    #[test]
+    fn combine_with_edited_mask() {
+        let curve = random_tolerance_curve();
+        let (t, x, y) = (&curve.timestamps, &curve.x, &curve.y);
+        let mut mask = douglas_peucker_mask(t, x, y, 3.0).unwrap();
+
+        assert_curve(
+            &combine_with_mask(t, x, y, &mask).unwrap(),
+            &decimate_by_tolerance(t, x, y, 3.0).unwrap(),
+        );
+
+        mask[1] = true;
+        let forced = combine_with_mask(t, x, y, &mask).unwrap();
+        assert_eq!(forced.timestamps[1], t[1]);
+
+        assert!(combine_with_mask(t, x, y, &mask[1..]).is_err());
+        mask[0] = false;
+        assert!(combine_with_mask(t, x, y, &mask).is_err());
+    }
+
+    #[test]
+    fn multichannel_decimation_keeps_channels_aligned() {
+        let curve = random_tolerance_curve();
+        let speed: Vec<f64> = (0..curve.len()).map(|i| i as f64 * 10.0).collect();
+        let mut multichannel = curve.add_channel("speed", speed).unwrap();
+        multichannel
+            .add_channel("index", (0..curve.len()).map(|i| i as f64).collect())
+            .unwrap();
+        assert!(multichannel.add_channel("short", vec![1.0]).is_err());
+        assert_eq!(multichannel.channel_names(), vec!["index", "speed"]);
+
+        let decimated = decimate_by_tolerance_multichannel(&multichannel, 3.0).unwrap();
+        let retained = decimated.channel("index").unwrap();
+
+        assert_curve(
+            &decimated.get_curve(),
+            &decimate_by_tolerance(&curve.timestamps, &curve.x, &curve.y, 3.0).unwrap(),
+        );
+        assert_eq!(retained.len(), decimated.get_curve().len());
+
+        for (i, (&index, &speed)) in retained
             .iter()
-            .map(|&x| x as f64)
-            .collect(),
+            .zip(decimated.channel("speed").unwrap())
+            .enumerate()
+        {
+            assert_eq!(
+                decimated.curve.timestamps[i],
+                curve.timestamps[index as usize]
+            );
+            assert_eq!(speed, index * 10.0);
+        }
+
+        assert!(decimated.channel("missing").is_none());
+    }
+
+    #[test]
+    fn table_string_aligns_columns() {
+        let curve = Curve {
+            timestamps: vec![0.0, 10.0],
+            x: vec![1.5, -2.0],
+            y: vec![100.0, 3.0],
         };
 
-        assert_curve(&result, &expected_curve);
+        assert_eq!(
+            curve.to_table_string(),
+            " t |   x |   y\n---+-----+----\n 0 | 1.5 | 100\n10 |  -2 |   3\n"
+        );
+    }
+
+    #[test]
+    fn trim_by_arc_length() {
+        let curve = random_tolerance_curve();
+        let total = curve.compute_length();
+
+        assert_curve(&curve.trim(0.0, 1.0).unwrap(), &curve);
+
+        let first_half = curve.trim(0.0, 0.5).unwrap();
+        assert!((first_half.compute_length() - total / 2.0).abs() < 1e-9);
+        assert_eq!(first_half.get_first(), curve.get_first());
+
+        let middle = curve.trim(0.25, 0.75).unwrap();
+        assert!((middle.compute_length() - total / 2.0).abs() < 1e-9);
+        assert_eq!(middle.get_first(), curve.sample_at_fraction(0.25));
+        assert_eq!(middle.get_last(), curve.sample_at_fraction(0.75));
+
+        assert!(curve.trim(0.5, 0.5).is_err());
+        assert!(curve.trim(-0.1, 0.5).is_err());
+        assert!(curve.trim(0.5, 1.1).is_err());
+    }
+
+    #[test]
+    fn parallel_resample_is_symmetric() {
+        let curve = random_tolerance_curve();
+        let center = curve.resample_by_arc_length(25).unwrap();
+        let (left, right) = curve.parallel_resample(25).unwrap();
+
+        assert_eq!(left.len(), 25);
+        assert_eq!(right.len(), 25);
+        assert_eq!(left.timestamps, center.timestamps);
+
+        for i in 0..center.len() {
+            assert!(((left.x[i] + right.x[i]) / 2.0 - center.x[i]).abs() < 1e-9);
+            assert!(((left.y[i] + right.y[i]) / 2.0 - center.y[i]).abs() < 1e-9);
+        }
+
+        assert_curve(&left, &center.offset_curve(1.0));
+        assert!(curve.parallel_resample(1).is_err());
+    }
+
+    #[test]
+    fn total_rotation_of_circle_and_line() {
+        let angles: Vec<f64> = (0..=36)
+            .map(|i| i as f64 * std::f64::consts::TAU / 36.0)
+            .collect();
+        let mut circle = Curve {
+            timestamps: angles.clone(),
+            x: angles.iter().map(|a| a.cos()).collect(),
+            y: angles.iter().map(|a| a.sin()).collect(),
+        };
+        // make the path end exactly where it started
+        circle.x[36] = circle.x[0];
+        circle.y[36] = circle.y[0];
+
+        assert!((circle.compute_total_rotation() - std::f64::consts::TAU).abs() < 1e-9);
+        assert!((circle.reversed().compute_total_rotation() + std::f64::consts::TAU).abs() < 1e-9);
+
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 1.0, 2.0, 3.0],
+            y: vec![0.0, 2.0, 4.0, 6.0],
+        };
+        assert_eq!(line.compute_total_rotation(), 0.0);
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        assert_eq!(base64_encode(b"Man"), "TWFu");
+        assert_eq!(base64_encode(b"Ma"), "TWE=");
+        assert_eq!(base64_encode(b"M"), "TQ==");
+        assert_eq!(base64_decode("TWE=").unwrap(), b"Ma");
+        assert!(base64_decode("TWE*").is_err());
+        assert!(base64_decode("TWFuT").is_err());
+
+        let curve = random_tolerance_curve();
+        let encoded = base64_encode(&curve.to_binary());
+        assert_eq!(encoded.len(), (8 + curve.len() * 24).div_ceil(3) * 4);
+        assert_curve(&Curve::from_base64(&encoded).unwrap(), &curve);
+    }
+
+    #[test]
+    fn path_complexity_of_line_and_sine() {
+        let timestamps: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let line = Curve {
+            timestamps: timestamps.clone(),
+            x: timestamps.clone(),
+            y: timestamps.iter().map(|t| t * 0.5).collect(),
+        };
+        assert_eq!(line.compute_path_complexity(), 0.0);
+
+        let sine = Curve {
+            timestamps: timestamps.clone(),
+            x: timestamps.clone(),
+            y: timestamps.iter().map(|t| (t / 5.0).sin() * 10.0).collect(),
+        };
+        let complexity = sine.compute_path_complexity();
+        assert!(complexity > 0.1 && complexity <= 1.0, "{}", complexity);
+    }
+
+    #[test]
+    fn segment_velocities_of_uniform_acceleration() {
+        let timestamps: Vec<f64> = (0..6).map(|i| i as f64).collect();
+        let curve = Curve {
+            timestamps: timestamps.clone(),
+            x: timestamps.iter().map(|t| t * t * 0.3).collect(),
+            y: timestamps.iter().map(|t| t * t * 0.4).collect(),
+        };
+
+        let velocities = curve.compute_segment_velocities();
+        assert_eq!(velocities.len(), 5);
+        for (i, v) in velocities.iter().enumerate() {
+            assert!((v - (i as f64 + 0.5)).abs() < 1e-12);
+        }
+
+        let accelerations = curve.compute_segment_accelerations();
+        assert_eq!(accelerations.len(), 4);
+        assert!(accelerations.iter().all(|a| (a - 1.0).abs() < 1e-12));
+
+        let stalled = Curve {
+            timestamps: vec![0.0, 0.0, 1.0],
+            x: vec![0.0, 1.0, 2.0],
+            y: vec![0.0, 0.0, 0.0],
+        };
+        assert_eq!(
+            stalled.compute_segment_velocities(),
+            vec![f64::INFINITY, 1.0]
+        );
+    }
+
+    #[test]
+    fn bearing_changes_of_u_turn_and_circle() {
+        let u_turn = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 0.0, 1.0, 1.0],
+            y: vec![0.0, 1.0, 1.0, 0.0],
+        };
+        assert_eq!(u_turn.compute_bearing_changes(), vec![0.0, 90.0, 90.0, 0.0]);
+
+        let reversal = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 0.0],
+            y: vec![0.0, 0.0, 0.0],
+        };
+        assert_eq!(reversal.compute_bearing_changes()[1], 180.0);
+
+        // one step past a full turn, so the 36 interior points each turn by 10 degrees
+        let angles: Vec<f64> = (0..=37)
+            .map(|i| i as f64 * std::f64::consts::TAU / 36.0)
+            .collect();
+        let circle = Curve {
+            timestamps: angles.clone(),
+            x: angles.iter().map(|a| a.cos()).collect(),
+            y: angles.iter().map(|a| a.sin()).collect(),
+        };
+        let total: f64 = circle
+            .compute_bearing_changes()
+            .iter()
+            .map(|c| c.abs())
+            .sum();
+        assert!((total - 360.0).abs() < 1e-9, "{}", total);
+    }
+
+    #[test]
+    fn prune_stationary_runs() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+            x: vec![0.0, 10.0, 10.1, 10.0, 10.1, 10.0, 20.0, 20.05, 30.0],
+            y: vec![0.0; 9],
+        };
+
+        let pruned = curve.prune_stationary_segments(1.0, 2.0);
+        assert_eq!(pruned.timestamps, vec![0.0, 1.0, 5.0, 6.0, 7.0, 8.0]);
+
+        // no run lasts long enough
+        assert_curve(&curve.prune_stationary_segments(1.0, 10.0), &curve);
+        assert_curve(&curve.prune_stationary_segments(0.0, 0.0), &curve);
+    }
+
+    #[test]
+    fn spatial_extent_is_largest_pairwise_distance() {
+        let pruned = decimate_by_tolerance(
+            &[0.0, 1.0, 2.0, 3.0, 4.0],
+            &[0.0, 1.0, 2.0, 3.0, 4.0],
+            &[0.0, 0.5, 1.0, 1.5, 2.0],
+            100.0,
+        )
+        .unwrap();
+        assert_eq!(
+            pruned.compute_spatial_extent(),
+            distance(0.0, 0.0, 4.0, 2.0)
+        );
+
+        let triangle = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 3.0, 0.0],
+            y: vec![0.0, 0.0, 4.0],
+        };
+        assert_eq!(triangle.compute_spatial_extent(), 5.0);
+
+        let single = Curve {
+            timestamps: vec![0.0],
+            x: vec![1.0],
+            y: vec![1.0],
+        };
+        assert_eq!(single.compute_spatial_extent(), 0.0);
+    }
+
+    #[test]
+    fn simplify_preserving_area_of_circle() {
+        let angles: Vec<f64> = (0..100)
+            .map(|i| i as f64 * std::f64::consts::TAU / 100.0)
+            .collect();
+        let circle = Curve {
+            timestamps: angles.clone(),
+            x: angles.iter().map(|a| 10.0 * a.cos()).collect(),
+            y: angles.iter().map(|a| 10.0 * a.sin()).collect(),
+        };
+        let area = |curve: &Curve| {
+            let n = curve.len();
+            (0..n)
+                .map(|i| curve.x[i] * curve.y[(i + 1) % n] - curve.x[(i + 1) % n] * curve.y[i])
+                .sum::<f64>()
+                / 2.0
+        };
+        let original = area(&circle);
+
+        for fraction in [0.001, 0.01, 0.05] {
+            let simplified = circle.simplify_preserving_area(fraction);
+            assert!(simplified.len() < circle.len());
+            assert!((area(&simplified) - original).abs() <= fraction * original);
+        }
+
+        assert!(
+            circle.simplify_preserving_area(0.05).len()
+                < circle.simplify_preserving_area(0.001).len()
+        );
+    }
+
+    #[test]
+    fn wkb_matches_spec() {
+        let curve = Curve {
+            timestamps: vec![5.0, 6.0],
+            x: vec![1.0, 3.0],
+            y: vec![2.0, 4.0],
+        };
+        let wkb = curve.encode_wkb();
+
+        assert_eq!(&wkb[..9], &[1, 0xea, 0x03, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(&wkb[9..17], &1.0f64.to_le_bytes());
+        assert_eq!(&wkb[25..33], &5.0f64.to_le_bytes());
+        assert_eq!(wkb.len(), 9 + 2 * 24);
+        assert_curve(&Curve::decode_wkb(&wkb).unwrap(), &curve);
+
+        // the same line as a big-endian two-dimensional LineString
+        let mut big_endian = vec![0, 0, 0, 0, 2, 0, 0, 0, 2];
+        for value in [1.0f64, 2.0, 3.0, 4.0] {
+            big_endian.extend_from_slice(&value.to_be_bytes());
+        }
+        assert_curve(
+            &Curve::decode_wkb(&big_endian).unwrap(),
+            &Curve {
+                timestamps: vec![0.0, 1.0],
+                x: vec![1.0, 3.0],
+                y: vec![2.0, 4.0],
+            },
+        );
+
+        assert!(Curve::decode_wkb(&big_endian[..20]).is_err());
+        assert!(Curve::decode_wkb(&[1, 1, 0, 0, 0]).is_err());
+        assert!(Curve::decode_wkb(&[2]).is_err());
+        assert!(Curve::decode_wkb(&[1, 2, 0]).is_err());
+
+        // 0x1000_0001 * 16 wraps around to 16 in 32-bit arithmetic
+        let mut oversized = vec![1, 2, 0, 0, 0, 0x01, 0, 0, 0x10];
+        oversized.extend_from_slice(&[0; 16]);
+        assert!(Curve::decode_wkb(&oversized).is_err());
+    }
+
+    #[test]
+    fn render_horizontal_line() {
+        let line = Curve {
+            timestamps: vec![0.0, 1.0],
+            x: vec![0.0, 10.0],
+            y: vec![3.0, 3.0],
+        };
+        let pixels = line.render_to_pixels(8, 5, 0x11223380);
+        let pixel = |column: usize, row: usize| {
+            let offset = (row * 8 + column) * 4;
+            &pixels[offset..offset + 4]
+        };
+
+        assert_eq!(pixels.len(), 8 * 5 * 4);
+        // a flat line lands on the middle row
+        for column in 1..7 {
+            assert_eq!(pixel(column, 2), &[0x11, 0x22, 0x33, 0x80]);
+        }
+        assert_eq!(pixel(0, 2)[3], 0x40);
+        assert!((0..8).all(|column| pixel(column, 1)[3] == 0 && pixel(column, 3)[3] == 0));
+
+        assert!(line.render_to_pixels(0, 5, 0xffffffff).is_empty());
+    }
+
+    #[test]
+    fn render_diagonal_is_anti_aliased() {
+        let diagonal = Curve {
+            timestamps: vec![0.0, 1.0],
+            x: vec![0.0, 2.0],
+            y: vec![0.0, 1.0],
+        };
+        let pixels = diagonal.render_to_pixels(9, 5, 0x000000ff);
+        let alpha = |column: usize, row: usize| pixels[(row * 9 + column) * 4 + 3];
+
+        // the line climbs half a row per column, splitting odd columns between two rows
+        assert_eq!(alpha(4, 2), 255);
+        assert_eq!(alpha(3, 2), 128);
+        assert_eq!(alpha(3, 3), 128);
+        assert_eq!(alpha(8, 0), 128);
+    }
+
+    #[test]
+    fn find_loops_of_figure_eight() {
+        let t: Vec<f64> = (0..=40)
+            .map(|i| i as f64 * std::f64::consts::TAU / 40.0)
+            .collect();
+        let figure_eight = Curve {
+            timestamps: t.clone(),
+            x: t.iter().map(|t| t.sin()).collect(),
+            y: t.iter().map(|t| t.sin() * t.cos()).collect(),
+        };
+
+        assert_eq!(figure_eight.find_loops(0.1), vec![(0, 20), (20, 40)]);
+        assert!(figure_eight.find_loops(10.0).is_empty());
+
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            y: vec![0.0; 5],
+        };
+        assert!(line.find_loops(0.0).is_empty());
+    }
+
+    #[test]
+    fn local_extrema_timestamps_of_sine() {
+        let timestamps: Vec<f64> = (0..=40).map(|i| i as f64 * 0.25).collect();
+        let sine = Curve {
+            timestamps: timestamps.clone(),
+            x: timestamps.clone(),
+            y: timestamps
+                .iter()
+                .map(|t| (t * std::f64::consts::FRAC_PI_2).sin())
+                .collect(),
+        };
+
+        assert_eq!(sine.compute_local_maxima_timestamps(), vec![1.0, 5.0, 9.0]);
+        assert_eq!(sine.compute_local_minima_timestamps(), vec![3.0, 7.0]);
+    }
+
+    #[test]
+    fn point_curvature_matches_full_computation() {
+        let curve = random_tolerance_curve();
+        let curvature = curve.compute_signed_curvature();
+
+        for (i, &k) in curvature.iter().enumerate().skip(1).take(curve.len() - 2) {
+            assert_eq!(curve.compute_point_curvature_at(i), Some(k));
+        }
+
+        assert_eq!(curve.compute_point_curvature_at(0), None);
+        assert_eq!(curve.compute_point_curvature_at(curve.len() - 1), None);
+        assert_eq!(curve.compute_point_curvature_at(curve.len()), None);
+
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 2.0],
+            y: vec![0.0, 1.0, 2.0],
+        };
+        assert_eq!(line.compute_point_curvature_at(1), Some(0.0));
+    }
+
+    #[test]
+    fn noise_level_of_line_and_zigzag() {
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 1.0, 2.0, 3.0],
+            y: vec![0.0, 2.0, 4.0, 6.0],
+        };
+        assert_eq!(line.estimate_noise_level(), 0.0);
+
+        // a single bump one unit off the line
+        let zigzag = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            y: vec![0.0, 1.0, 0.0, 0.0, 0.0],
+        };
+        let deviations = [1.0, 1.0 / 5f64.sqrt(), 0.0];
+        let mean = deviations.iter().sum::<f64>() / 3.0;
+        let expected = (deviations.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / 3.0).sqrt();
+        assert!((zigzag.estimate_noise_level() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn inflections_of_sine() {
+        let timestamps: Vec<f64> = (0..=32)
+            .map(|i| i as f64 * std::f64::consts::PI / 8.0)
+            .collect();
+        let sine = Curve {
+            timestamps: timestamps.clone(),
+            x: timestamps.clone(),
+            y: timestamps.iter().map(|t| t.sin()).collect(),
+        };
+        let inflections = sine.find_inflection_timestamps();
+
+        assert_eq!(inflections.len(), 3);
+        for (i, t) in inflections.iter().enumerate() {
+            assert!((t - (i + 1) as f64 * std::f64::consts::PI).abs() < 1e-9);
+        }
+
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 2.0],
+            y: vec![0.0, 1.0, 2.0],
+        };
+        assert!(line.find_inflection_timestamps().is_empty());
+    }
+
+    #[test]
+    fn autocorrelation_length_of_line_and_circle() {
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 1.0, 2.0, 3.0],
+            y: vec![0.0, 2.0, 4.0, 6.0],
+        };
+        assert_eq!(line.compute_autocorrelation_length(), f64::INFINITY);
+
+        // the increments of a circle turn by a fixed angle, so their correlation is its cosine
+        let step = 0.05f64;
+        let angles: Vec<f64> = (0..200).map(|i| i as f64 * step).collect();
+        let circle = Curve {
+            timestamps: angles.clone(),
+            x: angles.iter().map(|a| a.cos()).collect(),
+            y: angles.iter().map(|a| a.sin()).collect(),
+        };
+        let segment = 2.0 * (step / 2.0).sin();
+        let expected = (-1.0f64).exp().acos() / step * segment;
+        let length = circle.compute_autocorrelation_length();
+        assert!((length - expected).abs() < 0.01 * expected, "{}", length);
+    }
+
+    #[test]
+    fn duration_normalized_timestamps() {
+        let curve = Curve {
+            timestamps: vec![10.0, 12.0, 13.0, 20.0],
+            x: vec![0.0, 1.0, 2.0, 3.0],
+            y: vec![4.0, 5.0, 6.0, 7.0],
+        };
+        let normalized = curve.to_duration_normalized();
+
+        assert_eq!(normalized.timestamps, vec![0.0, 0.2, 0.3, 1.0]);
+        assert_eq!(normalized.x, curve.x);
+        assert_eq!(normalized.y, curve.y);
+
+        let single = Curve {
+            timestamps: vec![5.0],
+            x: vec![1.0],
+            y: vec![1.0],
+        };
+        assert_eq!(single.to_duration_normalized().timestamps, vec![0.0]);
+    }
+
+    #[test]
+    fn bidirectional_decimation_ignores_direction() {
+        let curve = random_tolerance_curve();
+
+        for tolerance in [0.5, 3.0, 10.0] {
+            let forward = curve
+                .apply_ramer_douglas_peucker_bidirectional(tolerance)
+                .unwrap();
+            let backward = curve
+                .reversed()
+                .apply_ramer_douglas_peucker_bidirectional(tolerance)
+                .unwrap();
+
+            assert_curve(&forward, &backward.reversed());
+        }
+
+        // each direction breaks the tie between the two bumps differently, so neither is kept
+        let square_wave = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 1.0, 2.0, 3.0],
+            y: vec![0.0, 1.0, 1.0, 0.0],
+        };
+        assert_eq!(
+            square_wave
+                .apply_ramer_douglas_peucker_bidirectional(0.5)
+                .unwrap()
+                .timestamps,
+            vec![0.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn segment_angles_by_compass_direction() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            x: vec![0.0, 0.0, 1.0, 1.0, 0.0],
+            y: vec![0.0, 1.0, 1.0, 0.0, 0.0],
+        };
+
+        assert_eq!(
+            curve.compute_segment_angles(),
+            vec![0.0, 90.0, 180.0, 270.0]
+        );
+    }
+
+    #[test]
+    fn median_smoothing_removes_spike() {
+        let timestamps: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let mut y: Vec<f64> = timestamps.iter().map(|t| (t * 0.7).sin()).collect();
+        y[10] += 100.0;
+        let curve = Curve {
+            timestamps: timestamps.clone(),
+            x: timestamps.clone(),
+            y,
+        };
+
+        let median = curve.smooth_median(3).unwrap();
+        let gaussian = curve.smooth_gaussian(1.0, 3).unwrap();
+
+        assert!(median.y.iter().all(|y| y.abs() <= 1.0));
+        assert!(gaussian.y[10] > 10.0);
+        // the endpoints have no neighbours on one side and keep their values
+        assert_eq!(median.y[0], curve.y[0]);
+        assert_eq!(median.x, curve.x);
+
+        assert!(curve.smooth_median(4).is_err());
+        assert!(curve.smooth_median(0).is_err());
+    }
+
+    #[test]
+    fn monotone_x_segments_reconstruct_curve() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+            x: vec![0.0, 1.0, 2.0, 2.0, 1.0, 0.5, 0.5, 3.0],
+            y: vec![0.0, 1.0, 0.0, 1.0, 2.0, 1.0, 0.0, 1.0],
+        };
+        let segments = curve.split_into_monotone_x_segments();
+
+        assert_eq!(segments.len(), 3);
+        assert!(segments.iter().all(|segment| segment.is_monotone_in_x()));
+
+        let mut rebuilt = segments[0].clone();
+        for segment in &segments[1..] {
+            assert_eq!(segment.get_first(), rebuilt.get_last());
+            rebuilt
+                .timestamps
+                .extend_from_slice(&segment.timestamps[1..]);
+            rebuilt.x.extend_from_slice(&segment.x[1..]);
+            rebuilt.y.extend_from_slice(&segment.y[1..]);
+        }
+        assert_curve(&rebuilt, &curve);
+
+        let single = random_tolerance_curve().select(&[0]);
+        assert_eq!(single.split_into_monotone_x_segments().len(), 1);
+    }
+
+    #[test]
+    fn protobuf_matches_reference_encoding() {
+        let curve = Curve {
+            timestamps: vec![0.0, 1.5],
+            x: vec![1.0, -2.0],
+            y: vec![0.25, 3.0],
+        };
+        let mut expected = Vec::new();
+        for (key, values) in [
+            (0x0a, [0.0f64, 1.5]),
+            (0x12, [1.0, -2.0]),
+            (0x1a, [0.25, 3.0]),
+        ] {
+            // the key and a 16 byte length, then the packed little-endian doubles
+            expected.extend_from_slice(&[key, 16]);
+            for value in values {
+                expected.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        let encoded = curve.encode_protobuf();
+        assert_eq!(encoded, expected);
+        assert_curve(&Curve::decode_protobuf(&encoded).unwrap(), &curve);
+
+        // unpacked doubles and an unknown varint field 4 decode the same way
+        let mut unpacked = vec![0x20, 0x96, 0x01];
+        for (key, value) in [
+            (0x09, 0.0f64),
+            (0x11, 1.0),
+            (0x19, 0.25),
+            (0x09, 1.5),
+            (0x11, -2.0),
+            (0x19, 3.0),
+        ] {
+            unpacked.push(key);
+            unpacked.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_curve(&Curve::decode_protobuf(&unpacked).unwrap(), &curve);
+
+        let mut buffer = Vec::new();
+        write_varint(&mut buffer, 300);
+        assert_eq!(buffer, vec![0xac, 0x02]);
+        assert_eq!(read_varint(&buffer, &mut 0), Ok(300));
+
+        assert!(Curve::decode_protobuf(&encoded[..20]).is_err());
+        assert!(Curve::decode_protobuf(&encoded[..36]).is_err());
+        assert!(Curve::decode_protobuf(&[0x0b]).is_err());
+        assert_eq!(Curve::decode_protobuf(&[]).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn interpolate_missing_fills_gaps() {
+        let curve = Curve {
+            timestamps: vec![0.0, 10.0, 40.0, 45.0],
+            x: vec![0.0, 1.0, 4.0, 5.0],
+            y: vec![0.0, 0.0, 30.0, 30.0],
+        };
+        let filled = curve.interpolate_missing(10.0);
+
+        assert_curve(
+            &filled,
+            &Curve {
+                timestamps: vec![0.0, 10.0, 20.0, 30.0, 40.0, 45.0],
+                x: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+                y: vec![0.0, 0.0, 10.0, 20.0, 30.0, 30.0],
+            },
+        );
+        assert_eq!(curve.interpolate_missing(12.0).len(), curve.len() + 2);
+        assert_curve(&curve.interpolate_missing(100.0), &curve);
+        assert_curve(&curve.interpolate_missing(0.0), &curve);
+    }
+
+    #[test]
+    fn average_heading_wraps_around_north() {
+        let (a, b) = (10f64.to_radians(), 350f64.to_radians());
+        let curve = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, a.sin(), a.sin() + b.sin()],
+            y: vec![0.0, a.cos(), a.cos() + b.cos()],
+        };
+        let heading = curve.compute_average_heading().unwrap();
+        assert!(heading.min(360.0 - heading) < 1e-9, "{}", heading);
+
+        let east_and_south = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 1.0, 1.0, 1.0],
+            y: vec![0.0, 0.0, 0.0, -1.0],
+        };
+        assert!((east_and_south.compute_average_heading().unwrap() - 135.0).abs() < 1e-9);
+
+        let shuttle = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 0.0],
+            y: vec![0.0, 0.0, 0.0],
+        };
+        assert_eq!(shuttle.compute_average_heading(), None);
+        assert_eq!(shuttle.select(&[0]).compute_average_heading(), None);
+    }
+
+    #[test]
+    fn convex_hull_area_of_square_and_u_shape() {
+        let square = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0],
+            x: vec![0.0, 2.0, 2.0, 0.0],
+            y: vec![0.0, 0.0, 2.0, 2.0],
+        };
+        assert_eq!(square.compute_area(), 4.0);
+        assert_eq!(square.compute_convex_hull_area(), square.compute_area());
+
+        let u_shape = Curve {
+            timestamps: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+            x: vec![0.0, 0.0, 3.0, 3.0, 2.0, 2.0, 1.0, 1.0],
+            y: vec![2.0, 0.0, 0.0, 2.0, 2.0, 1.0, 1.0, 2.0],
+        };
+        assert_eq!(u_shape.compute_area(), 5.0);
+        assert_eq!(u_shape.compute_convex_hull_area(), 6.0);
+
+        let line = Curve {
+            timestamps: vec![0.0, 1.0, 2.0],
+            x: vec![0.0, 1.0, 2.0],
+            y: vec![0.0, 1.0, 2.0],
+        };
+        assert_eq!(line.compute_convex_hull_area(), 0.0);
+        assert_eq!(line.select(&[0]).compute_convex_hull_area(), 0.0);
+    }
+
+    #[test]
+    fn uniform_curves_from_functions() {
+        let parabola = Curve::create_uniform_from_range(-2.0, 2.0, |x| x * x, 5);
+        assert_curve(
+            &parabola,
+            &Curve {
+                timestamps: vec![-2.0, -1.0, 0.0, 1.0, 2.0],
+                x: vec![-2.0, -1.0, 0.0, 1.0, 2.0],
+                y: vec![4.0, 1.0, 0.0, 1.0, 4.0],
+            },
+        );
+
+        let sine = Curve::create_uniform_from_range(0.0, std::f64::consts::TAU, f64::sin, 41);
+        assert_eq!(sine.len(), 41);
+        assert_eq!(sine.get_last().unwrap().1, std::f64::consts::TAU);
+        assert_eq!(sine.compute_local_maxima_timestamps().len(), 1);
+
+        // the only corners of a step are either side of the jump at x = 5
+        let step =
+            Curve::create_uniform_from_range(0.0, 10.0, |x| if x < 5.0 { 0.0 } else { 1.0 }, 11);
+        let decimated = decimate_by_tolerance(&step.timestamps, &step.x, &step.y, 0.01).unwrap();
+        assert_eq!(decimated.timestamps, vec![0.0, 4.0, 5.0, 10.0]);
+
+        assert_eq!(
+            Curve::create_uniform_from_range(3.0, 4.0, |x| x, 1).x,
+            vec![3.0]
+        );
+        assert!(Curve::create_uniform_from_range(3.0, 4.0, |x| x, 0).is_empty());
     }
 }
@@ -0,0 +1,145 @@
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Float64Array, Function, Object, Reflect};
+use rdp::{decimate_by_tolerance, decimate_many, Curve};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::CanvasRenderingContext2d;
+
+// run with `wasm-pack test --headless --chrome`
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn decimate_many_curves() {
+    let line = decimate_by_tolerance(
+        &[0.0, 1.0, 2.0, 3.0, 4.0],
+        &[0.0, 1.0, 2.0, 3.0, 4.0],
+        &[0.0, 0.5, 1.0, 1.5, 2.0],
+        0.0,
+    )
+    .unwrap();
+    let zigzag = decimate_by_tolerance(
+        &[0.0, 1.0, 2.0, 3.0, 4.0],
+        &[0.0, 1.9, 4.0, 5.0, 4.0],
+        &[0.0, 0.5, 1.0, 1.5, 2.0],
+        0.0,
+    )
+    .unwrap();
+
+    let curves = Array::of3(
+        &line.into(),
+        &zigzag.into(),
+        &JsValue::from_str("not a curve"),
+    );
+    let result = decimate_many(curves.clone(), 100.0);
+
+    assert_eq!(result.length(), 3);
+    assert!(result.get(2).is_null());
+
+    for i in 0..2 {
+        let decimated: Curve =
+            wasm_bindgen::convert::TryFromJsValue::try_from_js_value(result.get(i)).unwrap();
+        assert_eq!(decimated.get_timestamps().to_vec(), vec![0.0, 4.0]);
+    }
+
+    // the input curves are still usable after the batch
+    let line: Curve =
+        wasm_bindgen::convert::TryFromJsValue::try_from_js_value(curves.get(0)).unwrap();
+    assert_eq!(line.len(), 2);
+}
+
+fn zigzag_curve() -> Curve {
+    decimate_by_tolerance(
+        &[0.0, 1.0, 2.0, 3.0, 4.0],
+        &[0.0, 1.9, 4.0, 5.0, 4.0],
+        &[0.0, 0.5, 1.0, 1.5, 2.0],
+        0.5,
+    )
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn getters_return_decimated_arrays() {
+    let curve = zigzag_curve();
+
+    let timestamps: Float64Array = curve.get_timestamps();
+    let x: Float64Array = curve.get_x();
+    let y: Float64Array = curve.get_y();
+
+    assert_eq!(timestamps.to_vec(), vec![0.0, 2.0, 3.0, 4.0]);
+    assert_eq!(x.to_vec(), vec![0.0, 4.0, 5.0, 4.0]);
+    assert_eq!(y.to_vec(), vec![0.0, 1.0, 1.5, 2.0]);
+}
+
+#[wasm_bindgen_test]
+fn csv_round_trip() {
+    let curve = zigzag_curve();
+    let csv = String::from(curve.get_csv());
+
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("timestamps, x, y"));
+
+    let rows: Vec<Vec<f64>> = lines
+        .map(|line| {
+            line.split(", ")
+                .map(|field| field.parse().unwrap())
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(rows.len(), curve.len());
+
+    for (i, row) in rows.iter().enumerate() {
+        let (t, x, y) = curve.get_point(i).unwrap();
+        assert_eq!(row, &vec![t, x, y]);
+    }
+}
+
+#[wasm_bindgen_test]
+fn debug_print_logs() {
+    zigzag_curve().debug_print();
+}
+
+#[wasm_bindgen_test]
+fn canvas_path_follows_points() {
+    // a stand-in context that records the path calls it receives
+    let ctx = Object::new();
+    Reflect::set(&ctx, &"calls".into(), &Array::new()).unwrap();
+
+    for name in ["moveTo", "lineTo"] {
+        let record =
+            Function::new_with_args("x, y", &format!("this.calls.push(['{}', x, y])", name));
+        Reflect::set(&ctx, &name.into(), &record).unwrap();
+    }
+
+    let ctx: CanvasRenderingContext2d = ctx.unchecked_into();
+    let curve = zigzag_curve();
+    curve.to_canvas_path_scaled(&ctx, 2.0, -1.0, 10.0, 0.0);
+
+    let calls = Array::from(&Reflect::get(&ctx, &"calls".into()).unwrap());
+    assert_eq!(calls.length() as usize, curve.len());
+
+    for (i, call) in calls.iter().enumerate() {
+        let call = Array::from(&call);
+        let (_, x, y) = curve.get_point(i).unwrap();
+        let name = if i == 0 { "moveTo" } else { "lineTo" };
+        assert_eq!(call.get(0), JsValue::from(name));
+        assert_eq!(call.get(1).as_f64(), Some(x * 2.0 + 10.0));
+        assert_eq!(call.get(2).as_f64(), Some(-y));
+    }
+}
+
+#[wasm_bindgen_test]
+fn debug_print_table_logs() {
+    zigzag_curve().debug_print_table();
+}
+
+#[wasm_bindgen_test]
+fn base64_round_trip() {
+    let curve = zigzag_curve();
+    let encoded = String::from(curve.encode_to_base64());
+    let decoded = Curve::decode_from_base64(&encoded).unwrap();
+
+    assert_eq!(decoded.get_binary().to_vec(), curve.get_binary().to_vec());
+    assert!(Curve::decode_from_base64("not base64").is_err());
+}